@@ -0,0 +1,315 @@
+#[cfg(feature = "search")]
+mod search;
+#[cfg(feature = "search")]
+pub use search::*;
+
+#[cfg(feature = "ingest")]
+mod ingest;
+#[cfg(feature = "ingest")]
+pub use ingest::*;
+
+#[cfg(feature = "control")]
+mod control;
+#[cfg(feature = "control")]
+pub use control::*;
+
+use std::future::Future;
+use std::io;
+use std::time::Instant;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::sync::Mutex;
+
+use crate::channels::{
+    closed_by_server, parse_banner_version, ChannelMode, HealthCheck, ServerInfo,
+    UNINITIALIZED_MODE_MAX_BUFFER_SIZE,
+};
+#[cfg(feature = "ingest")]
+use crate::commands::SplitCommand;
+use crate::commands::{PingCommand, StartCommand, StreamCommand};
+use crate::protocol::{self, Protocol};
+use crate::result::*;
+
+/// Asynchronous version of the [`SonicStream`](crate::SonicStream) built on top of
+/// the tokio runtime.
+///
+/// The connection is guarded by an async mutex, so a request and its reply are
+/// always paired even if the same channel is used from several tasks.
+///
+/// The commands are cancellation-safe: if the future of the command is dropped,
+/// e.g. by `tokio::time::timeout`, before the reply is read, the next command
+/// reads and discards the late reply first. If the request wasn't written
+/// completely, the connection is broken and the next commands fail.
+///
+/// Note: This requires enabling the `async` feature.
+#[derive(Debug)]
+pub struct AsyncSonicStream {
+    connection: Mutex<Connection>,
+    mode: ChannelMode,
+    max_buffer_size: usize,
+    protocol: Protocol,
+    banner: String,
+}
+
+#[derive(Debug)]
+struct Connection {
+    stream: BufReader<TcpStream>,
+    /// Bytes of the line which is being read. They are kept if the command is
+    /// cancelled, so the next command reads the rest of the line.
+    line: Vec<u8>,
+    state: ConnectionState,
+}
+
+/// The state is updated before every await point, so it's left as is when the
+/// future of the command is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectionState {
+    /// There is no command in flight.
+    Ready,
+    /// The request was sent, but the reply isn't read yet.
+    AwaitingReply,
+    /// The request wasn't written completely or the reply cannot be read.
+    Broken,
+}
+
+impl AsyncSonicStream {
+    async fn send(&self, connection: &mut Connection, req: protocol::Request) -> Result<()> {
+        let buf = self
+            .protocol
+            .format_request(req)
+            .map_err(Error::write_to_stream)?;
+        connection
+            .stream
+            .get_mut()
+            .write_all(&buf)
+            .await
+            .map_err(Error::write_to_stream)?;
+        Ok(())
+    }
+
+    async fn read_line(
+        &self,
+        connection: &mut Connection,
+        line: &mut String,
+    ) -> Result<protocol::Response> {
+        line.clear();
+        // Unlike `read_line`, `read_until` keeps the partially read bytes in
+        // the buffer if the future is dropped.
+        match connection
+            .stream
+            .read_until(b'\n', &mut connection.line)
+            .await
+        {
+            Ok(0) => return Err(Error::read_stream(closed_by_server())),
+            Err(err) => return Err(Error::read_stream(err)),
+            Ok(_) => {}
+        }
+        line.push_str(&String::from_utf8_lossy(&connection.line));
+        connection.line.clear();
+
+        log::debug!("[channel] {}", line);
+        self.protocol.parse_response(line)
+    }
+
+    /// Reads the reply to the sent request, skipping the `PENDING` line.
+    async fn read_reply(
+        &self,
+        connection: &mut Connection,
+        line: &mut String,
+    ) -> Result<protocol::Response> {
+        let res = loop {
+            match self.read_line(connection, line).await {
+                Ok(protocol::Response::Pending(_)) => continue,
+                res => break res,
+            }
+        };
+        connection.state = match res {
+            Err(Error::ReadStream { .. }) => ConnectionState::Broken,
+            _ => ConnectionState::Ready,
+        };
+        res
+    }
+
+    /// Discards the reply to the cancelled command.
+    async fn recover(&self, connection: &mut Connection) -> Result<()> {
+        match connection.state {
+            ConnectionState::Ready => Ok(()),
+            ConnectionState::AwaitingReply => {
+                log::debug!("[channel] discarding reply to the cancelled command");
+                let mut line = String::new();
+                match self.read_reply(connection, &mut line).await {
+                    Err(err @ Error::ReadStream { .. }) => Err(err),
+                    _ => Ok(()),
+                }
+            }
+            ConnectionState::Broken => Err(Error::write_to_stream(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the connection is broken by the cancelled or failed command",
+            ))),
+        }
+    }
+
+    pub(crate) async fn run_command<SC: StreamCommand>(&self, command: SC) -> Result<SC::Response> {
+        let req = command.request();
+        req.validate()?;
+        let name = req.name();
+
+        let mut connection = self.connection.lock().await;
+        self.recover(&mut connection)
+            .await
+            .map_err(|err| err.in_command(name, ""))?;
+
+        connection.state = ConnectionState::Broken;
+        self.send(&mut connection, req)
+            .await
+            .map_err(|err| err.in_command(name, ""))?;
+        connection.state = ConnectionState::AwaitingReply;
+
+        let mut line = String::with_capacity(self.max_buffer_size);
+        let res = self
+            .read_reply(&mut connection, &mut line)
+            .await
+            .map_err(|err| err.in_command(name, &line))?;
+        command
+            .receive(res)
+            .map_err(|err| err.in_command(name, &line))
+    }
+
+    /// Runs the command as several commands if it doesn't fit the buffer of the
+    /// server. Returns the responses of all sent commands.
+    #[cfg(feature = "ingest")]
+    pub(crate) async fn run_split_command<SC: SplitCommand>(
+        &self,
+        command: SC,
+    ) -> Result<Vec<SC::Response>> {
+        let mut responses = Vec::new();
+        for command in command.split(self.protocol, self.max_buffer_size) {
+            responses.push(self.run_command(command).await?);
+        }
+        Ok(responses)
+    }
+
+    /// Returns information about the server of the connection.
+    pub fn server_info(&self) -> ServerInfo {
+        ServerInfo {
+            version: parse_banner_version(&self.banner),
+            banner: self.banner.clone(),
+            protocol_version: self.protocol.version() as u8,
+            max_buffer_size: self.max_buffer_size,
+            mode: self.mode,
+        }
+    }
+
+    async fn connect<A: ToSocketAddrs>(mode: ChannelMode, addr: A) -> Result<Self> {
+        let stream = TcpStream::connect(addr)
+            .await
+            .map_err(Error::ConnectToServer)?;
+
+        let mut channel = AsyncSonicStream {
+            connection: Mutex::new(Connection {
+                stream: BufReader::new(stream),
+                line: Vec::new(),
+                state: ConnectionState::Ready,
+            }),
+            mode,
+            max_buffer_size: UNINITIALIZED_MODE_MAX_BUFFER_SIZE,
+            protocol: Default::default(),
+            banner: String::new(),
+        };
+
+        let mut line = String::new();
+        let res = {
+            let mut connection = channel.connection.lock().await;
+            channel.read_line(&mut connection, &mut line).await?
+        };
+        match res {
+            protocol::Response::Connected(banner) => {
+                channel.banner = banner;
+                Ok(channel)
+            }
+            _ => Err(Error::ConnectToServer(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unexpected greeting `{}`", line.trim_end()),
+            ))),
+        }
+    }
+
+    async fn start<S: ToString>(&mut self, password: S) -> Result<()> {
+        let res = self
+            .run_command(StartCommand {
+                mode: self.mode,
+                password: password.to_string(),
+            })
+            .await?;
+
+        self.max_buffer_size = res.max_buffer_size;
+        self.protocol = Protocol::from(res.protocol_version);
+
+        Ok(())
+    }
+
+    /// Connect to the search backend in chosen mode.
+    pub(crate) async fn connect_with_start<A, S>(
+        mode: ChannelMode,
+        addr: A,
+        password: S,
+    ) -> Result<Self>
+    where
+        A: ToSocketAddrs,
+        S: ToString,
+    {
+        let mut channel = Self::connect(mode, addr).await?;
+        channel.start(password).await?;
+        Ok(channel)
+    }
+}
+
+/// This trait should be implemented for all supported asynchronous sonic channels
+///
+/// Note: This requires enabling the `async` feature.
+pub trait AsyncSonicChannel {
+    /// Sonic channel struct
+    type Channel;
+
+    /// Returns reference for async sonic stream of connection
+    fn stream(&self) -> &AsyncSonicStream;
+
+    /// Returns information about the sonic server. See
+    /// [`ServerInfo`](crate::ServerInfo) for more information.
+    fn server_info(&self) -> ServerInfo {
+        self.stream().server_info()
+    }
+
+    /// Pings the server and returns the round-trip latency with information
+    /// about the server. See [`HealthCheck`](crate::HealthCheck) for more
+    /// information.
+    fn health_check(&self) -> impl Future<Output = Result<HealthCheck>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            let started = Instant::now();
+            self.stream().run_command(PingCommand).await?;
+            Ok(HealthCheck::new(started.elapsed(), self.server_info()))
+        }
+    }
+
+    /// Connects to sonic backend and run start command.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> result::Result<()> {
+    /// let search_channel = AsyncSearchChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// ).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn start<A, S>(addr: A, password: S) -> impl Future<Output = Result<Self::Channel>> + Send
+    where
+        A: ToSocketAddrs + Send,
+        S: ToString + Send;
+}
@@ -0,0 +1,235 @@
+use super::{AsyncSonicChannel, AsyncSonicStream};
+use crate::channels::ChannelMode;
+use crate::commands::*;
+use crate::result::Result;
+use tokio::net::ToSocketAddrs;
+
+/// Asynchronous version of the [`ControlChannel`](crate::ControlChannel).
+///
+/// ### Available commands
+///
+/// In this mode you can use `consolidate`, `backup`, `restore`, `info`,
+/// `help`, `ping` and `quit` commands.
+///
+/// **Note:** This mode requires enabling the `async` and `control` features.
+#[derive(Debug)]
+pub struct AsyncControlChannel(AsyncSonicStream);
+
+impl AsyncSonicChannel for AsyncControlChannel {
+    type Channel = AsyncControlChannel;
+
+    fn stream(&self) -> &AsyncSonicStream {
+        &self.0
+    }
+
+    async fn start<A, S>(addr: A, password: S) -> Result<Self::Channel>
+    where
+        A: ToSocketAddrs + Send,
+        S: ToString + Send,
+    {
+        AsyncSonicStream::connect_with_start(ChannelMode::Control, addr, password)
+            .await
+            .map(Self)
+    }
+}
+
+impl AsyncControlChannel {
+    init_async_command!(
+        /// Stop connection.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let channel = AsyncControlChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// channel.quit().await?;
+        /// # Ok(())
+        /// # }
+        use QuitCommand for fn quit();
+    );
+
+    init_async_command!(
+        /// Ping server.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let channel = AsyncControlChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// channel.ping().await?;
+        /// # Ok(())
+        /// # }
+        use PingCommand for fn ping();
+    );
+
+    init_async_command!(
+        /// Show the manual of the server, e.g. the list of available commands.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let channel = AsyncControlChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// let manuals = channel.help(HelpRequest::Manuals).await?;
+        /// dbg!(manuals.entries);
+        /// # Ok(())
+        /// # }
+        use HelpCommand<'_> for fn help(
+            req: HelpRequest<'_>,
+        )
+    );
+
+    /// Returns the commands available in the channel mode.
+    ///
+    /// It can be used to detect the capabilities of the server.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> result::Result<()> {
+    /// let channel = AsyncControlChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// ).await?;
+    ///
+    /// let commands = channel.commands().await?;
+    /// assert!(commands.contains(&String::from("PING")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn commands(&self) -> Result<Vec<String>> {
+        self.help(HelpRequest::commands())
+            .await
+            .map(|res| res.entries)
+    }
+}
+
+impl AsyncControlChannel {
+    init_async_command!(
+        /// Trigger control action.
+        ///
+        /// Note: This method requires enabling the `control` feature and start connection in
+        /// Control mode
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let control_channel = AsyncControlChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// control_channel.trigger(TriggerRequest::Consolidate).await?;
+        /// # Ok(())
+        /// # }
+        use TriggerCommand<'_> for fn trigger(
+            req: TriggerRequest<'_>,
+        )
+    );
+
+    init_async_command!(
+        /// Get the statistics of the server, e.g. uptime, connected clients
+        /// and command latency.
+        ///
+        /// Note: This method requires enabling the `control` feature and start
+        /// connection in Control mode.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let control_channel = AsyncControlChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// let stats = control_channel.info().await?;
+        /// println!("uptime: {:?}", stats.uptime);
+        /// # Ok(())
+        /// # }
+        use InfoCommand for fn info();
+    );
+
+    /// Consolidate indexed search data instead of waiting for the next automated
+    /// consolidation tick.
+    ///
+    /// Note: This method requires enabling the `control` feature and start
+    /// connection in Control mode.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> result::Result<()> {
+    /// let control_channel = AsyncControlChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// ).await?;
+    ///
+    /// control_channel.consolidate().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn consolidate(&self) -> Result<()> {
+        self.trigger(TriggerRequest::Consolidate).await
+    }
+
+    /// Backup KV + FST to <path>/<BACKUP_{KV/FST}_PATH>
+    /// See [sonic backend source code](https://github.com/valeriansaliou/sonic/blob/master/src/channel/command.rs#L808)
+    /// for more information.
+    ///
+    /// Note: This method requires enabling the `control` feature and start
+    /// connection in Control mode.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> result::Result<()> {
+    /// let control_channel = AsyncControlChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// ).await?;
+    ///
+    /// control_channel.backup("2020-08-07T23-48").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn backup(&self, path: &str) -> Result<()> {
+        self.trigger(TriggerRequest::Backup(path)).await
+    }
+
+    /// Restore KV + FST from <path> if you already have backup with the same name.
+    ///
+    /// Note: This method requires enabling the `control` feature and start
+    /// connection in Control mode.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> result::Result<()> {
+    /// let control_channel = AsyncControlChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// ).await?;
+    ///
+    /// let result = control_channel.restore("2020-08-07T23-48").await?;
+    /// assert_eq!(result, ());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn restore(&self, path: &str) -> Result<()> {
+        self.trigger(TriggerRequest::Restore(path)).await
+    }
+}
@@ -0,0 +1,238 @@
+use super::{AsyncSonicChannel, AsyncSonicStream};
+use crate::channels::ChannelMode;
+use crate::commands::*;
+use crate::result::Result;
+use tokio::net::ToSocketAddrs;
+
+/// Asynchronous version of the [`IngestChannel`](crate::IngestChannel).
+///
+/// ### Available commands
+///
+/// In this mode you can use `push`, `pop`, `flush`, `count`, `help`, `ping` and `quit` commands.
+///
+/// **Note:** This mode requires enabling the `async` and `ingest` features.
+#[derive(Debug)]
+pub struct AsyncIngestChannel(AsyncSonicStream);
+
+impl AsyncSonicChannel for AsyncIngestChannel {
+    type Channel = AsyncIngestChannel;
+
+    fn stream(&self) -> &AsyncSonicStream {
+        &self.0
+    }
+
+    async fn start<A, S>(addr: A, password: S) -> Result<Self::Channel>
+    where
+        A: ToSocketAddrs + Send,
+        S: ToString + Send,
+    {
+        AsyncSonicStream::connect_with_start(ChannelMode::Ingest, addr, password)
+            .await
+            .map(Self)
+    }
+}
+
+impl AsyncIngestChannel {
+    init_async_command!(
+        /// Stop connection.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let channel = AsyncIngestChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// channel.quit().await?;
+        /// # Ok(())
+        /// # }
+        use QuitCommand for fn quit();
+    );
+
+    init_async_command!(
+        /// Ping server.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let channel = AsyncIngestChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// channel.ping().await?;
+        /// # Ok(())
+        /// # }
+        use PingCommand for fn ping();
+    );
+
+    init_async_command!(
+        /// Show the manual of the server, e.g. the list of available commands.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let channel = AsyncIngestChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// let manuals = channel.help(HelpRequest::Manuals).await?;
+        /// dbg!(manuals.entries);
+        /// # Ok(())
+        /// # }
+        use HelpCommand<'_> for fn help(
+            req: HelpRequest<'_>,
+        )
+    );
+
+    /// Returns the commands available in the channel mode.
+    ///
+    /// It can be used to detect the capabilities of the server.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> result::Result<()> {
+    /// let channel = AsyncIngestChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// ).await?;
+    ///
+    /// let commands = channel.commands().await?;
+    /// assert!(commands.contains(&String::from("PING")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn commands(&self) -> Result<Vec<String>> {
+        self.help(HelpRequest::commands())
+            .await
+            .map(|res| res.entries)
+    }
+}
+
+impl AsyncIngestChannel {
+    /// Push search data in the index. Returns the count of sent push commands.
+    ///
+    /// If the request doesn't fit the buffer size of the server, the text is
+    /// split on words and pushed by several commands.
+    ///
+    /// Note: This method requires enabling the `ingest` feature and start
+    /// connection in Ingest mode.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> result::Result<()> {
+    /// let ingest_channel = AsyncIngestChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// ).await?;
+    ///
+    /// let result = ingest_channel.push(PushRequest::new(
+    ///     Dest::col("search").obj("recipe:295"),
+    ///     "Sweet Teriyaki Beef Skewers"
+    /// )).await?;
+    /// assert_eq!(result, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn push(&self, req: PushRequest) -> Result<usize> {
+        let chunks = self.stream().run_split_command(PushCommand { req }).await?;
+        Ok(chunks.len())
+    }
+
+    /// Pop search data from the index. Returns removed words count as usize type.
+    ///
+    /// If the request doesn't fit the buffer size of the server, the text is
+    /// split on words and popped by several commands.
+    ///
+    /// Note: This method requires enabling the `ingest` feature and start
+    /// connection in Ingest mode.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> result::Result<()> {
+    /// let ingest_channel = AsyncIngestChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// ).await?;
+    ///
+    /// let dest = Dest::col("search").obj("recipe:295");
+    /// let result = ingest_channel.pop(PopRequest::new(dest, "beef")).await?;
+    /// assert_eq!(result, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn pop(&self, req: PopRequest) -> Result<usize> {
+        let counts = self.stream().run_split_command(PopCommand { req }).await?;
+        Ok(counts.into_iter().sum())
+    }
+
+    init_async_command!(
+        /// Flush all indexed data from collections.
+        ///
+        /// Note: This method requires enabling the `ingest` feature and start
+        /// connection in Ingest mode.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let ingest_channel = AsyncIngestChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// let flushc_count = ingest_channel.flush(FlushRequest::collection("search")).await?;
+        /// dbg!(flushc_count);
+        /// let flushb_count = ingest_channel.flush(FlushRequest::bucket("search", "default")).await?;
+        /// dbg!(flushb_count);
+        /// let flusho_count = ingest_channel.flush(
+        ///     FlushRequest::object("search", "default", "recipe:295")
+        /// ).await?;
+        /// dbg!(flusho_count);
+        /// # Ok(())
+        /// # }
+        /// ```
+        use FlushCommand for fn flush(
+            req: FlushRequest,
+        );
+    );
+
+    init_async_command!(
+        /// Count indexed search data of your collection.
+        ///
+        /// Note: This method requires enabling the `ingest` feature and start
+        /// connection in Ingest mode.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let ingest_channel = AsyncIngestChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// let bucket_count = ingest_channel.count(CountRequest::buckets("search")).await?;
+        /// dbg!(bucket_count);
+        /// let object_count = ingest_channel.count(CountRequest::objects("search", "default")).await?;
+        /// dbg!(object_count);
+        /// let word_count = ingest_channel.count(
+        ///     CountRequest::words("search", "default", "recipe:256")
+        /// ).await?;
+        /// dbg!(word_count);
+        /// # Ok(())
+        /// # }
+        /// ```
+        use CountCommand for fn count(
+            req: CountRequest,
+        );
+    );
+}
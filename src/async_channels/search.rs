@@ -0,0 +1,215 @@
+use super::{AsyncSonicChannel, AsyncSonicStream};
+use crate::channels::ChannelMode;
+use crate::commands::*;
+use crate::result::Result;
+use tokio::net::ToSocketAddrs;
+
+/// Asynchronous version of the [`SearchChannel`](crate::SearchChannel).
+///
+/// ### Available commands
+///
+/// In this mode you can use `query`, `suggest`, `list`, `help`, `ping` and `quit` commands.
+///
+/// **Note:** This mode requires enabling the `async` and `search` features.
+#[derive(Debug)]
+pub struct AsyncSearchChannel(AsyncSonicStream);
+
+impl AsyncSonicChannel for AsyncSearchChannel {
+    type Channel = AsyncSearchChannel;
+
+    fn stream(&self) -> &AsyncSonicStream {
+        &self.0
+    }
+
+    async fn start<A, S>(addr: A, password: S) -> Result<Self::Channel>
+    where
+        A: ToSocketAddrs + Send,
+        S: ToString + Send,
+    {
+        AsyncSonicStream::connect_with_start(ChannelMode::Search, addr, password)
+            .await
+            .map(Self)
+    }
+}
+
+impl AsyncSearchChannel {
+    init_async_command!(
+        /// Stop connection.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let channel = AsyncSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// channel.quit().await?;
+        /// # Ok(())
+        /// # }
+        use QuitCommand for fn quit();
+    );
+
+    init_async_command!(
+        /// Ping server.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let channel = AsyncSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// channel.ping().await?;
+        /// # Ok(())
+        /// # }
+        use PingCommand for fn ping();
+    );
+
+    init_async_command!(
+        /// Show the manual of the server, e.g. the list of available commands.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let channel = AsyncSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// let manuals = channel.help(HelpRequest::Manuals).await?;
+        /// dbg!(manuals.entries);
+        /// # Ok(())
+        /// # }
+        use HelpCommand<'_> for fn help(
+            req: HelpRequest<'_>,
+        )
+    );
+
+    /// Returns the commands available in the channel mode.
+    ///
+    /// It can be used to detect the capabilities of the server.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> result::Result<()> {
+    /// let channel = AsyncSearchChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// ).await?;
+    ///
+    /// let commands = channel.commands().await?;
+    /// assert!(commands.contains(&String::from("PING")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn commands(&self) -> Result<Vec<String>> {
+        self.help(HelpRequest::commands())
+            .await
+            .map(|res| res.entries)
+    }
+}
+
+impl AsyncSearchChannel {
+    init_async_command!(
+        /// Query objects in database.
+        ///
+        /// Note: This method requires enabling the `search` feature and start
+        /// connection in Search mode.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let search_channel = AsyncSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// let result = search_channel.query(QueryRequest::new(
+        ///     Dest::col("search"),
+        ///     "Beef",
+        /// )).await?;
+        /// dbg!(result);
+        ///
+        /// let result = search_channel.query(
+        ///     QueryRequest::new(Dest::col("search"), "Beef").limit(10)
+        /// ).await?;
+        /// dbg!(result);
+        /// # Ok(())
+        /// # }
+        /// ```
+        use QueryCommand for fn query(
+            req: QueryRequest,
+        );
+    );
+
+    init_async_command!(
+        /// Suggest auto-completes words.
+        ///
+        /// Note: This method requires enabling the `search` feature and start
+        /// connection in Search mode.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let search_channel = AsyncSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// let result = search_channel.suggest(
+        ///     SuggestRequest::new(Dest::col("search"), "Beef")
+        /// ).await?;
+        /// dbg!(result);
+        ///
+        /// let result = search_channel.suggest(
+        ///     SuggestRequest::new(Dest::col("search"), "Beef").limit(2)
+        /// ).await?;
+        /// dbg!(result);
+        /// # Ok(())
+        /// # }
+        /// ```
+        use SuggestCommand for fn suggest(
+            req: SuggestRequest,
+        );
+    );
+
+    init_async_command!(
+        /// Enumerates all words in an index.
+        ///
+        /// Note: This method requires enabling the `search` feature and start
+        /// connection in Search mode.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # #[tokio::main]
+        /// # async fn main() -> result::Result<()> {
+        /// let search_channel = AsyncSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// ).await?;
+        ///
+        /// let result = search_channel.list(
+        ///     ListRequest::new(Dest::col("search"))
+        /// ).await?;
+        /// dbg!(result);
+        ///
+        /// let result = search_channel.list(
+        ///     ListRequest::new(Dest::col("search")).limit(2)
+        /// ).await?;
+        /// dbg!(result);
+        /// # Ok(())
+        /// # }
+        /// ```
+        use ListCommand for fn list(
+            req: ListRequest,
+        );
+    );
+}
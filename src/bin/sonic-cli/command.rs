@@ -0,0 +1,241 @@
+use crate::import::ImportArgs;
+use crate::output::Output;
+use crate::session::Session;
+use clap::{Args, Subcommand};
+use sonic_channel::result::Result;
+use sonic_channel::*;
+
+const DEFAULT_BUCKET: &str = "default";
+
+/// Commands of the sonic server.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Queries the objects which contain the terms.
+    Query {
+        #[command(flatten)]
+        dest: DestArgs,
+        /// Search terms.
+        terms: String,
+        /// Maximum number of the objects.
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Number of the objects to skip.
+        #[arg(long)]
+        offset: Option<usize>,
+        /// ISO 639-3 code of the terms language, e.g. `eng`.
+        #[arg(long, value_parser = parse_lang)]
+        lang: Option<Lang>,
+    },
+
+    /// Suggests the words which start with the word.
+    Suggest {
+        #[command(flatten)]
+        dest: DestArgs,
+        /// Beginning of the word.
+        word: String,
+        /// Maximum number of the words.
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+
+    /// Lists the indexed words.
+    List {
+        #[command(flatten)]
+        dest: DestArgs,
+        /// Maximum number of the words.
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Number of the words to skip.
+        #[arg(long)]
+        offset: Option<usize>,
+    },
+
+    /// Pushes the text of the object to the index.
+    Push {
+        #[command(flatten)]
+        dest: DestArgs,
+        /// Object id.
+        object: String,
+        /// Text of the object.
+        text: String,
+        /// ISO 639-3 code of the text language, e.g. `eng`.
+        #[arg(long, value_parser = parse_lang)]
+        lang: Option<Lang>,
+    },
+
+    /// Pops the words of the text from the object index.
+    Pop {
+        #[command(flatten)]
+        dest: DestArgs,
+        /// Object id.
+        object: String,
+        /// Words to pop.
+        text: String,
+    },
+
+    /// Flushes the collection, the bucket or the object.
+    Flush {
+        #[command(flatten)]
+        target: TargetArgs,
+    },
+
+    /// Counts the buckets of the collection, the objects of the bucket or the
+    /// words of the object.
+    Count {
+        #[command(flatten)]
+        target: TargetArgs,
+    },
+
+    /// Triggers the action on the server.
+    Trigger {
+        #[command(subcommand)]
+        action: TriggerAction,
+    },
+
+    /// Shows the statistics of the server.
+    Info,
+
+    /// Imports the documents from the JSONL or CSV file by several ingest
+    /// channels.
+    Import(Box<ImportArgs>),
+}
+
+/// Collection and bucket of the search and ingest commands.
+#[derive(Debug, Args)]
+pub struct DestArgs {
+    /// Name of the collection.
+    collection: String,
+    /// Name of the bucket.
+    #[arg(short, long, default_value = DEFAULT_BUCKET)]
+    bucket: String,
+}
+
+impl DestArgs {
+    fn dest(&self) -> Dest {
+        Dest::col_buc(&self.collection, &self.bucket)
+    }
+}
+
+/// Collection, bucket and object of the flush and count commands.
+#[derive(Debug, Args)]
+pub struct TargetArgs {
+    /// Name of the collection.
+    collection: String,
+    /// Name of the bucket.
+    #[arg(short, long)]
+    bucket: Option<String>,
+    /// Object id.
+    #[arg(short, long, requires = "bucket")]
+    object: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TriggerAction {
+    /// Consolidates the indexed data.
+    Consolidate,
+    /// Backups the data to the directory of the server.
+    Backup {
+        /// Path of the backup directory.
+        path: String,
+    },
+    /// Restores the data from the directory of the server.
+    Restore {
+        /// Path of the backup directory.
+        path: String,
+    },
+}
+
+fn parse_lang(code: &str) -> std::result::Result<Lang, String> {
+    Lang::from_code(code).ok_or_else(|| format!("unknown language code `{}`", code))
+}
+
+impl Command {
+    /// Runs the command in the channel of its mode.
+    pub fn run(self, session: &mut Session) -> Result<Output> {
+        match self {
+            Command::Query {
+                dest,
+                terms,
+                limit,
+                offset,
+                lang,
+            } => {
+                let mut req = QueryRequest::new(dest.dest(), terms);
+                req.limit = limit;
+                req.offset = offset;
+                req.lang = lang;
+                let objects = session.search()?.query(req)?;
+                Ok(Output::items("object", objects))
+            }
+            Command::Suggest { dest, word, limit } => {
+                let mut req = SuggestRequest::new(dest.dest(), word);
+                req.limit = limit;
+                let words = session.search()?.suggest(req)?;
+                Ok(Output::items("word", words))
+            }
+            Command::List {
+                dest,
+                limit,
+                offset,
+            } => {
+                let mut req = ListRequest::new(dest.dest());
+                req.limit = limit;
+                req.offset = offset;
+                let words = session.search()?.list(req)?;
+                Ok(Output::items("word", words))
+            }
+            Command::Push {
+                dest,
+                object,
+                text,
+                lang,
+            } => {
+                let mut req = PushRequest::new(dest.dest().obj(object), text);
+                req.lang = lang;
+                session.ingest()?.push(req)?;
+                Ok(Output::Ok)
+            }
+            Command::Pop { dest, object, text } => {
+                let req = PopRequest::new(dest.dest().obj(object), text);
+                let count = session.ingest()?.pop(req)?;
+                Ok(Output::count("popped", count))
+            }
+            Command::Flush { target } => {
+                let req = match (target.bucket, target.object) {
+                    (Some(bucket), Some(object)) => {
+                        FlushRequest::object(target.collection, bucket, object)
+                    }
+                    (Some(bucket), None) => FlushRequest::bucket(target.collection, bucket),
+                    (None, _) => FlushRequest::collection(target.collection),
+                };
+                let count = session.ingest()?.flush(req)?;
+                Ok(Output::count("flushed", count))
+            }
+            Command::Count { target } => {
+                let req = match (target.bucket, target.object) {
+                    (Some(bucket), Some(object)) => {
+                        CountRequest::words(target.collection, bucket, object)
+                    }
+                    (Some(bucket), None) => CountRequest::objects(target.collection, bucket),
+                    (None, _) => CountRequest::buckets(target.collection),
+                };
+                let count = session.ingest()?.count(req)?;
+                Ok(Output::count("count", count))
+            }
+            Command::Trigger { action } => {
+                let control = session.control()?;
+                match action {
+                    TriggerAction::Consolidate => control.consolidate()?,
+                    TriggerAction::Backup { path } => control.backup(&path)?,
+                    TriggerAction::Restore { path } => control.restore(&path)?,
+                }
+                Ok(Output::Ok)
+            }
+            Command::Info => {
+                let stats = session.control()?.info()?;
+                Ok(Output::Stats(stats))
+            }
+            Command::Import(args) => args.run(session.builder()),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::output::Output;
+use clap::{Args, ValueEnum};
+use sonic_channel::import::*;
+use sonic_channel::result::{Error, Result};
+use sonic_channel::ChannelBuilder;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often the progress is printed.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Arguments of the `import` command.
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// JSONL or CSV file with the documents, or `-` for the standard input.
+    file: PathBuf,
+
+    /// Format of the documents. By default it's detected by the file extension:
+    /// `.jsonl`, `.ndjson` or `.csv`.
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+
+    /// Collection of all documents.
+    #[arg(long, conflicts_with = "collection_field")]
+    collection: Option<String>,
+
+    /// Field of the collection [default: collection]
+    #[arg(long)]
+    collection_field: Option<String>,
+
+    /// Bucket of all documents [default: default]
+    #[arg(long, conflicts_with = "bucket_field")]
+    bucket: Option<String>,
+
+    /// Field of the bucket.
+    #[arg(long)]
+    bucket_field: Option<String>,
+
+    /// Field of the object id.
+    #[arg(long, default_value = "id")]
+    object_field: String,
+
+    /// Fields of the text, which are joined with spaces.
+    #[arg(long = "text-field", default_value = "text")]
+    text_fields: Vec<String>,
+
+    /// Field with the ISO 639-3 code of the text language.
+    #[arg(long)]
+    lang_field: Option<String>,
+
+    /// Number of the parallel connections.
+    #[arg(short = 'j', long, default_value_t = DEFAULT_IMPORT_CONNECTIONS)]
+    connections: usize,
+
+    /// File to resume the failed import [default: <FILE>.checkpoint]
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Jsonl,
+    Csv,
+}
+
+impl From<Format> for ImportFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Jsonl => ImportFormat::Jsonl,
+            Format::Csv => ImportFormat::Csv,
+        }
+    }
+}
+
+impl ImportArgs {
+    fn is_stdin(&self) -> bool {
+        self.file == Path::new("-")
+    }
+
+    fn mapping(&self) -> FieldMapping {
+        let mut mapping = FieldMapping::default()
+            .object(&self.object_field)
+            .text(&self.text_fields);
+        if let Some(collection) = &self.collection {
+            mapping = mapping.collection(FieldSource::value(collection));
+        }
+        if let Some(field) = &self.collection_field {
+            mapping = mapping.collection(FieldSource::field(field));
+        }
+        if let Some(bucket) = &self.bucket {
+            mapping = mapping.bucket(FieldSource::value(bucket));
+        }
+        if let Some(field) = &self.bucket_field {
+            mapping = mapping.bucket(FieldSource::field(field));
+        }
+        if let Some(field) = &self.lang_field {
+            mapping = mapping.lang(field);
+        }
+        mapping
+    }
+
+    /// The checkpoint isn't written for the standard input by default, because
+    /// it cannot be read again.
+    fn checkpoint(&self) -> Option<PathBuf> {
+        match &self.checkpoint {
+            Some(path) => Some(path.clone()),
+            None if self.is_stdin() => None,
+            None => {
+                let mut path = self.file.clone().into_os_string();
+                path.push(".checkpoint");
+                Some(PathBuf::from(path))
+            }
+        }
+    }
+
+    /// Imports the documents by the new ingest channels.
+    pub fn run(self, builder: &ChannelBuilder) -> Result<Output> {
+        let format = match self.format {
+            Some(format) => format.into(),
+            None if self.is_stdin() => {
+                return Err(Error::InvalidOptions(String::from(
+                    "the format of the standard input must be set by --format",
+                )))
+            }
+            None => ImportFormat::from_path(&self.file).ok_or_else(|| {
+                Error::InvalidOptions(format!(
+                    "cannot detect format of `{}`, set it by --format",
+                    self.file.display()
+                ))
+            })?,
+        };
+
+        let checkpoint = self.checkpoint();
+        let mut importer = Importer::new(builder.clone())
+            .mapping(self.mapping())
+            .connections(self.connections);
+        if let Some(path) = &checkpoint {
+            importer = importer.checkpoint(path);
+        }
+        if io::stderr().is_terminal() {
+            let last_printed = Mutex::new(Instant::now());
+            importer = importer.on_progress(move |progress| {
+                let mut last_printed = last_printed.lock().unwrap();
+                if last_printed.elapsed() >= PROGRESS_INTERVAL {
+                    *last_printed = Instant::now();
+                    print_progress(progress);
+                }
+            });
+        }
+
+        let res = if self.is_stdin() {
+            importer.import(io::stdin(), format)
+        } else {
+            let file = std::fs::File::open(&self.file).map_err(Error::ReadDocuments)?;
+            importer.import(file, format)
+        };
+
+        if io::stderr().is_terminal() {
+            // Clears the progress line.
+            eprint!("\r\x1b[K");
+        }
+        if let Err(ImportError {
+            checkpoint: Some(path),
+            ..
+        }) = &res
+        {
+            eprintln!(
+                "The checkpoint is written to `{}`, run the same command to resume the import.",
+                path.display()
+            );
+        }
+        Ok(Output::Import(res?))
+    }
+}
+
+fn print_progress(progress: &ImportProgress) {
+    let documents = progress.imported + progress.skipped;
+    let rate = documents as f64 / progress.elapsed.as_secs_f64().max(f64::EPSILON);
+    let mut stderr = io::stderr().lock();
+    let _ = write!(
+        stderr,
+        "\r\x1b[Kimported {} documents, skipped {} ({:.0} documents/s)",
+        progress.imported, progress.skipped, rate
+    );
+    let _ = stderr.flush();
+}
@@ -0,0 +1,97 @@
+//! Command-line client of the sonic search backend.
+//!
+//! Runs the command given in the arguments, or starts the interactive shell
+//! if there is no command.
+
+// The binary requires Rust 1.74 or newer, unlike the library.
+#![allow(clippy::incompatible_msrv)]
+
+use clap::Parser;
+use sonic_channel::{ChannelBuilder, ConnectOptions};
+use std::process::ExitCode;
+
+mod command;
+mod import;
+mod output;
+mod repl;
+mod session;
+
+use command::Command;
+use output::Format;
+use session::Session;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "sonic-cli",
+    version,
+    about = "Command-line client of the sonic search backend"
+)]
+struct Cli {
+    /// Connection URL, e.g. `sonic://:SecretPassword@localhost:1491`. Overrides
+    /// the address and the password.
+    #[arg(long, env = "SONIC_URL", hide_env_values = true)]
+    url: Option<String>,
+
+    /// Address of the sonic server.
+    #[arg(short, long, env = "SONIC_ADDR", default_value = "localhost:1491")]
+    addr: String,
+
+    /// Password of the sonic server.
+    #[arg(
+        short,
+        long,
+        env = "SONIC_PASSWORD",
+        default_value = "SecretPassword",
+        hide_env_values = true,
+        hide_default_value = true
+    )]
+    password: String,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+impl Cli {
+    fn builder(&self) -> sonic_channel::result::Result<ChannelBuilder> {
+        match &self.url {
+            Some(url) => {
+                let mut options = ConnectOptions::parse(url)?;
+                // The mode is chosen by the command, so the mode of the URL is ignored.
+                options.mode = None;
+                Ok(ChannelBuilder::from_options(options))
+            }
+            None => Ok(ChannelBuilder::new(&self.addr, &self.password)),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let builder = match cli.builder() {
+        Ok(builder) => builder,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut session = Session::new(builder);
+    match cli.command {
+        Some(command) => match command.run(&mut session) {
+            Ok(output) => {
+                print!("{}", output.render(cli.format));
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("error: {}", err);
+                ExitCode::FAILURE
+            }
+        },
+        None => repl::run(session, cli.format),
+    }
+}
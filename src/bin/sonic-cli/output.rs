@@ -0,0 +1,199 @@
+use clap::ValueEnum;
+use serde_json::json;
+use sonic_channel::import::ImportReport;
+use sonic_channel::ServerStats;
+use std::fmt::Write;
+
+/// Format of the command output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human-readable table.
+    Table,
+    /// JSON document per command.
+    Json,
+}
+
+/// Result of the command.
+#[derive(Debug)]
+pub enum Output {
+    /// Object ids or words.
+    Items {
+        column: &'static str,
+        items: Vec<String>,
+    },
+    /// Number of the popped, flushed or counted items.
+    Count { name: &'static str, count: usize },
+    /// Statistics of the server.
+    Stats(ServerStats),
+    /// Result of the import.
+    Import(ImportReport),
+    /// The command succeeded without result.
+    Ok,
+}
+
+impl Output {
+    pub fn items(column: &'static str, items: Vec<String>) -> Self {
+        Self::Items { column, items }
+    }
+
+    pub fn count(name: &'static str, count: usize) -> Self {
+        Self::Count { name, count }
+    }
+
+    /// Renders the output with the trailing line ending.
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Table => self.render_table(),
+            Format::Json => format!("{}\n", self.to_json()),
+        }
+    }
+
+    fn render_table(&self) -> String {
+        match self {
+            Output::Items { column, items } => {
+                let rows = items.iter().map(|item| vec![item.clone()]).collect();
+                table(&[column], rows)
+            }
+            Output::Count { name, count } => table(&[name], vec![vec![count.to_string()]]),
+            Output::Stats(stats) => {
+                let rows = stats_rows(stats)
+                    .into_iter()
+                    .map(|(key, value)| vec![key.to_string(), value])
+                    .collect();
+                table(&["stat", "value"], rows)
+            }
+            Output::Import(report) => {
+                let rows = vec![
+                    vec![String::from("imported"), report.imported.to_string()],
+                    vec![String::from("skipped"), report.skipped.to_string()],
+                    vec![
+                        String::from("resumed_from"),
+                        report.resumed_from.to_string(),
+                    ],
+                    vec![
+                        String::from("elapsed"),
+                        format!("{:.1}s", report.elapsed.as_secs_f64()),
+                    ],
+                ];
+                table(&["stat", "value"], rows)
+            }
+            Output::Ok => String::from("OK\n"),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Output::Items { items, .. } => json!(items),
+            Output::Count { name, count } => json!({ *name: count }),
+            Output::Stats(stats) => json!({
+                "uptime": stats.uptime.as_secs(),
+                "clients_connected": stats.clients_connected,
+                "commands_total": stats.commands_total,
+                "command_latency_best": stats.command_latency_best.as_millis() as u64,
+                "command_latency_worst": stats.command_latency_worst.as_millis() as u64,
+                "kv_open_count": stats.kv_open_count,
+                "fst_open_count": stats.fst_open_count,
+                "fst_consolidate_count": stats.fst_consolidate_count,
+            }),
+            Output::Import(report) => json!({
+                "imported": report.imported,
+                "skipped": report.skipped,
+                "resumed_from": report.resumed_from,
+                "elapsed": report.elapsed.as_secs_f64(),
+            }),
+            Output::Ok => json!({ "ok": true }),
+        }
+    }
+}
+
+fn stats_rows(stats: &ServerStats) -> Vec<(&'static str, String)> {
+    vec![
+        ("uptime", format!("{}s", stats.uptime.as_secs())),
+        ("clients_connected", stats.clients_connected.to_string()),
+        ("commands_total", stats.commands_total.to_string()),
+        (
+            "command_latency_best",
+            format!("{}ms", stats.command_latency_best.as_millis()),
+        ),
+        (
+            "command_latency_worst",
+            format!("{}ms", stats.command_latency_worst.as_millis()),
+        ),
+        ("kv_open_count", stats.kv_open_count.to_string()),
+        ("fst_open_count", stats.fst_open_count.to_string()),
+        (
+            "fst_consolidate_count",
+            stats.fst_consolidate_count.to_string(),
+        ),
+    ]
+}
+
+/// Renders the rows with the header, aligning the columns by the widest cell.
+fn table(header: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths = header.iter().map(|h| h.chars().count()).collect::<Vec<_>>();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let mut write_row = |cells: &[&str]| {
+        let line = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        let _ = writeln!(out, "{}", line.trim_end());
+    };
+
+    write_row(header);
+    let separator = widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>();
+    write_row(&separator.iter().map(String::as_str).collect::<Vec<_>>());
+    for row in &rows {
+        write_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn should_render_aligned_table() {
+        let output = Output::items("object", vec![String::from("recipe:1"), String::from("a")]);
+        assert_eq!(
+            output.render(Format::Table),
+            "object\n--------\nrecipe:1\na\n"
+        );
+
+        let output = Output::Stats(ServerStats {
+            uptime: Duration::from_secs(42),
+            ..Default::default()
+        });
+        let table = output.render(Format::Table);
+        assert!(table.starts_with("stat                   value\n"));
+        assert!(table.contains("\nuptime                 42s\n"));
+    }
+
+    #[test]
+    fn should_render_json() {
+        let output = Output::items("word", vec![String::from("beef")]);
+        assert_eq!(output.render(Format::Json), "[\"beef\"]\n");
+
+        assert_eq!(
+            Output::count("popped", 3).render(Format::Json),
+            "{\"popped\":3}\n"
+        );
+
+        let output = Output::Stats(ServerStats {
+            command_latency_worst: Duration::from_millis(250),
+            ..Default::default()
+        });
+        let json: serde_json::Value = serde_json::from_str(&output.render(Format::Json)).unwrap();
+        assert_eq!(json["command_latency_worst"], 250);
+    }
+}
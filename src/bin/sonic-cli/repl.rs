@@ -0,0 +1,230 @@
+use crate::command::Command;
+use crate::output::{Format, Output};
+use crate::session::Session;
+use clap::{Parser, Subcommand, ValueEnum};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use sonic_channel::ChannelMode;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const HISTORY_FILE: &str = ".sonic_cli_history";
+
+/// Line of the interactive shell.
+#[derive(Debug, Parser)]
+#[command(
+    name = "sonic-cli",
+    about = "Commands of the interactive shell",
+    override_usage = "<COMMAND> [ARGS]",
+    no_binary_name = true,
+    disable_version_flag = true
+)]
+struct ReplLine {
+    #[command(subcommand)]
+    command: ReplCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ReplCommand {
+    #[command(flatten)]
+    Sonic(Command),
+
+    /// Reconnects in the mode.
+    Mode {
+        #[arg(value_enum)]
+        mode: Mode,
+    },
+
+    /// Changes the output format.
+    Format {
+        #[arg(value_enum)]
+        format: Format,
+    },
+
+    /// Pings the server.
+    Ping,
+
+    /// Closes the shell.
+    #[command(alias = "quit")]
+    Exit,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Mode {
+    Search,
+    Ingest,
+    Control,
+}
+
+impl From<Mode> for ChannelMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Search => ChannelMode::Search,
+            Mode::Ingest => ChannelMode::Ingest,
+            Mode::Control => ChannelMode::Control,
+        }
+    }
+}
+
+/// Runs the interactive shell until `exit` or the end of input.
+pub fn run(mut session: Session, mut format: Format) -> ExitCode {
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let history = history_path();
+    if let Some(path) = &history {
+        // The history file doesn't exist on the first run.
+        let _ = editor.load_history(path);
+    }
+
+    loop {
+        let prompt = match session.mode() {
+            Some(mode) => format!("sonic({})> ", mode),
+            None => String::from("sonic> "),
+        };
+
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                break;
+            }
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        let args = match split_args(line) {
+            Ok(args) => args,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                continue;
+            }
+        };
+
+        let command = match ReplLine::try_parse_from(args) {
+            Ok(repl_line) => repl_line.command,
+            Err(err) => {
+                // Prints the help too.
+                let _ = err.print();
+                continue;
+            }
+        };
+
+        let result = match command {
+            ReplCommand::Sonic(command) => command.run(&mut session),
+            ReplCommand::Mode { mode } => session.connect(mode.into()).map(|_| Output::Ok),
+            ReplCommand::Format { format: new_format } => {
+                format = new_format;
+                Ok(Output::Ok)
+            }
+            ReplCommand::Ping => session.ping().map(|_| Output::Ok),
+            ReplCommand::Exit => break,
+        };
+
+        match result {
+            Ok(output) => print!("{}", output.render(format)),
+            Err(err) => eprintln!("error: {}", err),
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Err(err) = editor.save_history(path) {
+            eprintln!("warning: cannot save history: {}", err);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
+
+/// Splits the line on whitespaces, keeping the quoted text as one argument.
+///
+/// Both single and double quotes are supported, and the backslash escapes the
+/// next character.
+fn split_args(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut in_arg = false;
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) => {
+                let escaped = chars.next().ok_or("unexpected end after `\\`")?;
+                arg.push(escaped);
+                in_arg = true;
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (_, Some(_)) => arg.push(c),
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (c, None) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut arg));
+                    in_arg = false;
+                }
+            }
+            (c, None) => {
+                arg.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if let Some(q) = quote {
+        return Err(format!("unclosed quote `{}`", q));
+    }
+    if in_arg {
+        args.push(arg);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_split_quoted_args() {
+        assert_eq!(
+            split_args(r#"push recipes "recipe:1"  'Beef "Skewers"' a\ b "" "#).unwrap(),
+            vec!["push", "recipes", "recipe:1", "Beef \"Skewers\"", "a b", ""]
+        );
+        assert!(split_args("query recipes \"beef").is_err());
+        assert!(split_args("query recipes \\").is_err());
+    }
+
+    #[test]
+    fn should_parse_repl_commands() {
+        let parse = |line| ReplLine::try_parse_from(split_args(line).unwrap()).map(|l| l.command);
+
+        assert!(matches!(
+            parse("mode control"),
+            Ok(ReplCommand::Mode {
+                mode: Mode::Control
+            })
+        ));
+        assert!(matches!(parse("quit"), Ok(ReplCommand::Exit)));
+        assert!(matches!(
+            parse("query recipes 'beef skewers' --limit 5"),
+            Ok(ReplCommand::Sonic(Command::Query { .. }))
+        ));
+        assert!(parse("mode unknown").is_err());
+    }
+}
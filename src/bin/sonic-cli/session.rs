@@ -0,0 +1,105 @@
+use sonic_channel::result::Result;
+use sonic_channel::*;
+
+/// The channel opened by the session.
+#[derive(Debug)]
+enum Channel {
+    Search(SearchChannel),
+    Ingest(IngestChannel),
+    Control(ControlChannel),
+}
+
+impl Channel {
+    fn mode(&self) -> ChannelMode {
+        match self {
+            Channel::Search(_) => ChannelMode::Search,
+            Channel::Ingest(_) => ChannelMode::Ingest,
+            Channel::Control(_) => ChannelMode::Control,
+        }
+    }
+}
+
+/// Connection to the sonic server, which is reopened in another mode when the
+/// command needs it.
+#[derive(Debug)]
+pub struct Session {
+    builder: ChannelBuilder,
+    channel: Option<Channel>,
+}
+
+impl Session {
+    pub fn new(builder: ChannelBuilder) -> Self {
+        Self {
+            builder,
+            channel: None,
+        }
+    }
+
+    /// Returns the builder of the channels.
+    pub fn builder(&self) -> &ChannelBuilder {
+        &self.builder
+    }
+
+    /// Returns the mode of the open channel.
+    pub fn mode(&self) -> Option<ChannelMode> {
+        self.channel.as_ref().map(Channel::mode)
+    }
+
+    /// Opens the channel in the mode, closing the channel of another mode.
+    pub fn connect(&mut self, mode: ChannelMode) -> Result<()> {
+        if self.mode() == Some(mode) {
+            return Ok(());
+        }
+
+        if let Some(channel) = self.channel.take() {
+            // The old channel is dropped anyway, so the error isn't interesting.
+            let _ = match channel {
+                Channel::Search(channel) => channel.quit(),
+                Channel::Ingest(channel) => channel.quit(),
+                Channel::Control(channel) => channel.quit(),
+            };
+        }
+
+        self.channel = Some(match mode {
+            ChannelMode::Search => Channel::Search(self.builder.start()?),
+            ChannelMode::Ingest => Channel::Ingest(self.builder.start()?),
+            ChannelMode::Control => Channel::Control(self.builder.start()?),
+        });
+        Ok(())
+    }
+
+    pub fn search(&mut self) -> Result<&SearchChannel> {
+        self.connect(ChannelMode::Search)?;
+        match &self.channel {
+            Some(Channel::Search(channel)) => Ok(channel),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn ingest(&mut self) -> Result<&IngestChannel> {
+        self.connect(ChannelMode::Ingest)?;
+        match &self.channel {
+            Some(Channel::Ingest(channel)) => Ok(channel),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn control(&mut self) -> Result<&ControlChannel> {
+        self.connect(ChannelMode::Control)?;
+        match &self.channel {
+            Some(Channel::Control(channel)) => Ok(channel),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Pings the server by the open channel, or by the search channel if
+    /// there is no channel yet.
+    pub fn ping(&mut self) -> Result<()> {
+        match &self.channel {
+            Some(Channel::Search(channel)) => channel.ping(),
+            Some(Channel::Ingest(channel)) => channel.ping(),
+            Some(Channel::Control(channel)) => channel.ping(),
+            None => self.search()?.ping(),
+        }
+    }
+}
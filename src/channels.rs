@@ -3,6 +3,11 @@ mod search;
 #[cfg(feature = "search")]
 pub use search::*;
 
+#[cfg(feature = "search")]
+mod multiplexed;
+#[cfg(feature = "search")]
+pub use multiplexed::*;
+
 #[cfg(feature = "ingest")]
 mod ingest;
 #[cfg(feature = "ingest")]
@@ -13,15 +18,45 @@ mod control;
 #[cfg(feature = "control")]
 pub use control::*;
 
-use std::cell::RefCell;
-use std::io::{BufRead, BufReader, Write};
-use std::net::{TcpStream, ToSocketAddrs};
+mod builder;
+pub use builder::*;
+
+mod codec;
+pub use codec::*;
+
+mod options;
+pub use options::*;
+
+mod connect_options;
+pub use connect_options::*;
+
+mod server_info;
+pub use server_info::*;
+
+mod transcript;
+pub use transcript::*;
+
+mod transport;
+pub use transport::*;
 
-use crate::commands::{StartCommand, StreamCommand};
+#[cfg(feature = "tls")]
+mod tls;
+#[cfg(feature = "tls")]
+pub use tls::*;
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::ToSocketAddrs;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "ingest")]
+use crate::commands::SplitCommand;
+use crate::commands::{PingCommand, StartCommand, StartCommandResponse, StreamCommand};
 use crate::protocol::{self, Protocol};
 use crate::result::*;
 
-const UNINITIALIZED_MODE_MAX_BUFFER_SIZE: usize = 200;
+pub(crate) const UNINITIALIZED_MODE_MAX_BUFFER_SIZE: usize = 200;
 
 /// Channel modes supported by sonic search backend.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -75,92 +110,411 @@ impl std::fmt::Display for ChannelMode {
     }
 }
 
-/// Root and Heart of this library.
-///
-/// You can connect to the sonic search backend and run all supported protocol methods.
-///
+pub(crate) type BoxedTransport = Box<dyn Transport>;
+
+/// Started connection with the protocol and buffer size negotiated by the
+/// start command.
 #[derive(Debug)]
-pub struct SonicStream {
-    stream: RefCell<TcpStream>,
-    reader: RefCell<BufReader<TcpStream>>,
-    mode: Option<ChannelMode>, // None – Uninitialized mode
-    max_buffer_size: usize,
+struct Connection {
+    reader: BufReader<BoxedTransport>,
     protocol: Protocol,
+    max_buffer_size: usize,
+    last_activity: Instant,
+    banner: String,
+    /// Name of the last sent command, which is added to the errors.
+    last_command: &'static str,
+    /// The last response line, which is added to the errors.
+    last_line: String,
+    /// The server replied `ENDED`, so the connection cannot run commands anymore.
+    ended: bool,
+    /// The last command never reached the server: the write failed or the
+    /// server ended the connection instead of the reply.
+    unhandled: bool,
 }
 
-impl SonicStream {
-    fn send<SC: StreamCommand>(&self, command: &SC) -> Result<()> {
+impl Connection {
+    fn new(transport: BoxedTransport) -> Self {
+        Self {
+            reader: BufReader::new(transport),
+            protocol: Protocol::default(),
+            max_buffer_size: UNINITIALIZED_MODE_MAX_BUFFER_SIZE,
+            last_activity: Instant::now(),
+            banner: String::new(),
+            last_command: "",
+            last_line: String::new(),
+            ended: false,
+            unhandled: false,
+        }
+    }
+
+    fn send(&mut self, req: protocol::Request) -> Result<()> {
         let buf = self
             .protocol
-            .format_request(command.request())
-            .map_err(|_| Error::WriteToStream)?;
-        self.stream
-            .borrow_mut()
+            .format_request(req)
+            .map_err(Error::write_to_stream)?;
+        let transport = self.reader.get_mut();
+        transport
             .write_all(&buf)
-            .map_err(|_| Error::WriteToStream)?;
+            .and_then(|_| transport.flush())
+            .map_err(|e| io_error(e, Error::write_to_stream))?;
         Ok(())
     }
 
-    fn read_line(&self) -> Result<protocol::Response> {
-        let line = {
-            let mut line = String::with_capacity(self.max_buffer_size);
-            self.reader
-                .borrow_mut()
-                .read_line(&mut line)
-                .map_err(|_| Error::ReadStream)?;
-            line
-        };
+    fn read_line(&mut self) -> Result<protocol::Response> {
+        self.last_line.clear();
+        match self.reader.read_line(&mut self.last_line) {
+            Ok(0) => return Err(Error::read_stream(closed_by_server())),
+            Err(err) => return Err(io_error(err, Error::read_stream)),
+            Ok(_) => {}
+        }
 
-        log::debug!("[channel] {}", &line);
-        self.protocol.parse_response(&line)
+        log::debug!("[channel] {}", &self.last_line);
+        self.protocol.parse_response(&self.last_line)
     }
 
-    pub(crate) fn run_command<SC: StreamCommand>(&self, command: SC) -> Result<SC::Response> {
-        self.send(&command)?;
-        let res = loop {
-            let res = self.read_line()?;
-            if !matches!(&res, protocol::Response::Pending(_)) {
-                break res;
+    fn exchange(&mut self, req: protocol::Request) -> Result<protocol::Response> {
+        self.last_command = req.name();
+        self.last_line.clear();
+        self.last_activity = Instant::now();
+        self.exchange_request(req)
+            .map_err(|err| self.error_context(err))
+    }
+
+    fn exchange_request(&mut self, req: protocol::Request) -> Result<protocol::Response> {
+        let is_quit = matches!(req, protocol::Request::Quit);
+        self.unhandled = true;
+        if self.ended {
+            return Err(Error::write_to_stream(closed_by_server()));
+        }
+        self.send(req)?;
+        self.unhandled = false;
+        loop {
+            match self.read_line() {
+                Ok(protocol::Response::Pending(_)) => continue,
+                Ok(protocol::Response::Ended) => {
+                    self.ended = true;
+                    if is_quit {
+                        return Ok(protocol::Response::Ended);
+                    }
+                    // The server ended the connection by itself (e.g. after
+                    // `tcp_timeout`) without running the command.
+                    self.unhandled = true;
+                    return Err(Error::read_stream(closed_by_server()));
+                }
+                res => return res,
+            }
+        }
+    }
+
+    /// Adds the last command and response line to the error.
+    fn error_context(&self, err: Error) -> Error {
+        err.in_command(self.last_command, &self.last_line)
+    }
+
+    fn handshake(&mut self, mode: ChannelMode, password: &str) -> Result<StartCommandResponse> {
+        match self.read_line()? {
+            protocol::Response::Connected(banner) => self.banner = banner,
+            _ => {
+                return Err(Error::ConnectToServer(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected greeting `{}`", self.last_line.trim_end()),
+                )))
             }
+        }
+
+        let command = StartCommand {
+            mode,
+            password: password.to_string(),
         };
-        command.receive(res)
+        let res = self.exchange(command.request())?;
+        let res = command
+            .receive(res)
+            .map_err(|err| self.error_context(err))?;
+
+        self.max_buffer_size = res.max_buffer_size;
+        self.protocol = Protocol::from(res.protocol_version);
+
+        Ok(res)
     }
+}
 
-    fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self> {
-        let stream = TcpStream::connect(addr).map_err(|_| Error::ConnectToServer)?;
-        let read_stream = stream.try_clone().map_err(|_| Error::ConnectToServer)?;
+/// Converts timeouts to the dedicated error, other errors to the fallback.
+fn io_error(err: io::Error, fallback: fn(io::Error) -> Error) -> Error {
+    match err.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Error::timeout(err),
+        _ => fallback(err),
+    }
+}
 
-        let channel = SonicStream {
-            reader: RefCell::new(BufReader::new(read_stream)),
-            stream: RefCell::new(stream),
-            mode: None,
-            max_buffer_size: UNINITIALIZED_MODE_MAX_BUFFER_SIZE,
-            protocol: Default::default(),
+/// The error of the connection which was closed by the server.
+pub(crate) fn closed_by_server() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "connection closed by the server",
+    )
+}
+
+/// Root and Heart of this library.
+///
+/// You can connect to the sonic search backend and run all supported protocol methods.
+///
+/// The stream remembers the connector, mode and password of the connection, so it can
+/// reconnect and start the channel again if the connection was closed by the server.
+/// See [`ReconnectPolicy`] for more information.
+///
+/// The stream is `Send` and `Sync`, so the channel can be shared between threads
+/// (e.g. in an `Arc`). Commands are run one at a time: the connection stays locked
+/// until the reply to the command is read, so requests and replies are always
+/// paired. Use the [`MultiplexedSearchChannel`] to have many search commands in
+/// flight at the same time.
+pub struct SonicStream {
+    inner: Arc<StreamInner>,
+    /// Stops the keepalive thread when the stream is dropped. The sender is
+    /// locked, because it isn't `Sync` before Rust 1.72.
+    _keepalive: Option<Mutex<mpsc::Sender<()>>>,
+}
+
+/// The state of the stream which is shared with the keepalive thread.
+struct StreamInner {
+    connection: Mutex<Connection>,
+    connector: Arc<dyn Connector>,
+    password: String,
+    reconnect: ReconnectPolicy,
+    mode: ChannelMode,
+    object_id_codec: Option<Arc<dyn ObjectIdCodec>>,
+}
+
+impl std::fmt::Debug for SonicStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SonicStream")
+            .field("connection", &self.inner.connection)
+            .field("reconnect", &self.inner.reconnect)
+            .field("mode", &self.inner.mode)
+            .field("keepalive", &self._keepalive.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl StreamInner {
+    fn lock_connection(&self) -> MutexGuard<'_, Connection> {
+        // A panic can't happen between writing the request and reading the reply,
+        // so the connection is still usable.
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn run_command<SC: StreamCommand>(&self, command: SC) -> Result<SC::Response> {
+        // The lock is held until the reply is read, so other threads cannot send
+        // their commands in between.
+        let mut connection = self.lock_connection();
+        let mut res = self.exchange(&mut connection, &command)?;
+
+        if let (Some(codec), Some(objects)) = (&self.object_id_codec, res.query_objects_mut()) {
+            for object in objects.iter_mut() {
+                // The object could be pushed without the codec, so a broken
+                // encoding shouldn't fail the whole query.
+                match codec.decode(object) {
+                    Ok(decoded) => *object = decoded,
+                    Err(err) => log::warn!("[channel] {}, returning it as is", err),
+                }
+            }
+        }
+        command
+            .receive(res)
+            .map_err(|err| connection.error_context(err))
+    }
+
+    /// Returns how many bytes the object id of the command grows when it's
+    /// encoded.
+    #[cfg(feature = "ingest")]
+    fn object_id_growth<SC: StreamCommand>(&self, command: &SC) -> usize {
+        let mut req = command.request();
+        match (&self.object_id_codec, req.object_mut()) {
+            (Some(codec), Some(object)) => codec.encode(object).len().saturating_sub(object.len()),
+            _ => 0,
+        }
+    }
+
+    /// Creates the request of the command with the encoded object id.
+    fn request<SC: StreamCommand>(&self, command: &SC) -> Result<protocol::Request> {
+        let mut req = command.request();
+        if let (Some(codec), Some(object)) = (&self.object_id_codec, req.object_mut()) {
+            *object = codec.encode(object);
+        }
+        req.validate()?;
+        Ok(req)
+    }
+
+    fn exchange<SC: StreamCommand>(
+        &self,
+        connection: &mut Connection,
+        command: &SC,
+    ) -> Result<protocol::Response> {
+        let policy = self.reconnect;
+        match connection.exchange(self.request(command)?) {
+            Err(err @ Error::Timeout { .. }) => {
+                // The late response cannot be paired with the next command, so
+                // the connection is no longer usable.
+                let _ = connection.reader.get_ref().shutdown();
+                Err(err)
+            }
+            Err(err @ (Error::WriteToStream { .. } | Error::ReadStream { .. }))
+                if policy.enabled =>
+            {
+                log::warn!("[channel] connection is broken, reconnecting");
+                // The server never saw the unhandled command, so any command can
+                // be sent again. Otherwise it may have been run already.
+                let unhandled = connection.unhandled;
+                *connection = self.reconnect()?;
+                if unhandled || (policy.retry_idempotent && command.is_idempotent()) {
+                    connection.exchange(self.request(command)?)
+                } else {
+                    Err(err)
+                }
+            }
+            res => res,
+        }
+    }
+
+    /// Sends `PING` if the connection has been idle for the interval.
+    ///
+    /// Returns the time until the next check and the error of the `PING` command.
+    fn keepalive(&self, interval: Duration) -> (Duration, Result<()>) {
+        let mut connection = match self.connection.try_lock() {
+            Ok(connection) => connection,
+            Err(TryLockError::Poisoned(err)) => err.into_inner(),
+            // A command is in flight, so the connection isn't idle.
+            Err(TryLockError::WouldBlock) => return (interval, Ok(())),
         };
 
-        let res = channel.read_line()?;
-        if matches!(res, protocol::Response::Connected) {
-            Ok(channel)
-        } else {
-            Err(Error::ConnectToServer)
+        let idle = connection.last_activity.elapsed();
+        if idle < interval {
+            return (interval - idle, Ok(()));
         }
+
+        log::debug!("[channel] keepalive");
+        let res = self
+            .exchange(&mut connection, &PingCommand)
+            .and_then(|res| PingCommand.receive(res));
+        (interval, res)
     }
 
-    fn start<S: ToString>(&mut self, mode: ChannelMode, password: S) -> Result<()> {
-        if self.mode.is_some() {
-            return Err(Error::RunCommand);
+    fn reconnect(&self) -> Result<Connection> {
+        let policy = self.reconnect;
+        let mut attempt = 1;
+        loop {
+            match self.open() {
+                Ok(connection) => return Ok(connection),
+                Err(err) if attempt >= policy.max_attempts => return Err(err),
+                Err(_) => {
+                    attempt += 1;
+                    thread::sleep(policy.backoff);
+                }
+            }
         }
+    }
 
-        let res = self.run_command(StartCommand {
-            mode,
-            password: password.to_string(),
-        })?;
+    fn open(&self) -> Result<Connection> {
+        let mut connection = Connection::new(self.connector.connect()?);
+        connection.handshake(self.mode, &self.password)?;
+        Ok(connection)
+    }
+}
 
-        self.max_buffer_size = res.max_buffer_size;
-        self.protocol = Protocol::from(res.protocol_version);
-        self.mode = Some(res.mode);
+/// Starts the thread which pings the idle connection until the returned sender
+/// is dropped.
+fn spawn_keepalive(inner: &Arc<StreamInner>, keepalive: Keepalive) -> Result<mpsc::Sender<()>> {
+    let (stop, stopped) = mpsc::channel::<()>();
+    let inner = Arc::downgrade(inner);
 
-        Ok(())
+    thread::Builder::new()
+        .name(String::from("sonic-channel-keepalive"))
+        .spawn(move || {
+            let mut wait = keepalive.interval;
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(wait) {
+                let inner = match inner.upgrade() {
+                    Some(inner) => inner,
+                    None => break,
+                };
+
+                let (next, res) = inner.keepalive(keepalive.interval);
+                wait = next;
+                if let Err(err) = res {
+                    log::warn!("[channel] keepalive failed: {}", err);
+                    if let Some(on_error) = &keepalive.on_error {
+                        on_error(&err);
+                    }
+                }
+            }
+        })
+        .map_err(Error::ConnectToServer)?;
+
+    Ok(stop)
+}
+
+impl SonicStream {
+    /// Returns information about the server of the current connection.
+    pub fn server_info(&self) -> ServerInfo {
+        let connection = self.inner.lock_connection();
+        ServerInfo {
+            version: parse_banner_version(&connection.banner),
+            banner: connection.banner.clone(),
+            protocol_version: connection.protocol.version() as u8,
+            max_buffer_size: connection.max_buffer_size,
+            mode: self.inner.mode,
+        }
+    }
+
+    pub(crate) fn run_command<SC: StreamCommand>(&self, command: SC) -> Result<SC::Response> {
+        self.inner.run_command(command)
+    }
+
+    /// Runs the command as several commands if it doesn't fit the buffer of the
+    /// server. Returns the responses of all sent commands.
+    #[cfg(feature = "ingest")]
+    pub(crate) fn run_split_command<SC: SplitCommand>(
+        &self,
+        command: SC,
+    ) -> Result<Vec<SC::Response>> {
+        let (protocol, max_buffer_size) = {
+            let connection = self.inner.lock_connection();
+            (connection.protocol, connection.max_buffer_size)
+        };
+        // The chunks are measured with the raw object id, so the budget leaves
+        // room for the encoded one.
+        let growth = self.inner.object_id_growth(&command);
+        command
+            .split(protocol, max_buffer_size.saturating_sub(growth))
+            .into_iter()
+            .map(|command| self.run_command(command))
+            .collect()
+    }
+
+    /// Splits the started stream to the write half, the buffered read half and
+    /// the negotiated protocol.
+    #[cfg(feature = "search")]
+    pub(crate) fn into_parts(
+        self,
+    ) -> Result<(BoxedTransport, BufReader<BoxedTransport>, Protocol)> {
+        drop(self._keepalive);
+        let inner = Arc::try_unwrap(self.inner).map_err(|_| {
+            Error::ConnectToServer(io::Error::new(
+                io::ErrorKind::Other,
+                "the stream is shared with other channels",
+            ))
+        })?;
+        let Connection {
+            reader, protocol, ..
+        } = inner
+            .connection
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        let writer = reader
+            .get_ref()
+            .try_clone()
+            .map_err(Error::ConnectToServer)?;
+        Ok((writer, reader, protocol))
     }
 
     /// Connect to the search backend in chosen mode.
@@ -172,9 +526,56 @@ impl SonicStream {
         A: ToSocketAddrs,
         S: ToString,
     {
-        let mut channel = Self::connect(addr)?;
-        channel.start(mode, password)?;
-        Ok(channel)
+        let addrs = addr
+            .to_socket_addrs()
+            .map_err(Error::ConnectToServer)?
+            .collect::<Vec<_>>();
+        let options = StreamOptions::default();
+        let connector = TcpConnector {
+            hosts: vec![HostAddrs::new(addrs)],
+            options: options.clone(),
+        };
+
+        Self::connect_with_connector(mode, Arc::new(connector), password, options)
+    }
+
+    pub(crate) fn connect_with_connector<S>(
+        mode: ChannelMode,
+        connector: Arc<dyn Connector>,
+        password: S,
+        options: StreamOptions,
+    ) -> Result<Self>
+    where
+        S: ToString,
+    {
+        if matches!(&options.keepalive, Some(keepalive) if keepalive.interval.is_zero()) {
+            return Err(Error::InvalidOptions(String::from(
+                "keepalive interval must be greater than zero",
+            )));
+        }
+
+        let password = password.to_string();
+
+        let mut connection = Connection::new(connector.connect()?);
+        let res = connection.handshake(mode, &password)?;
+
+        let inner = Arc::new(StreamInner {
+            connection: Mutex::new(connection),
+            connector,
+            password,
+            reconnect: options.reconnect,
+            mode: res.mode,
+            object_id_codec: options.object_id_codec,
+        });
+        let keepalive = match options.keepalive {
+            Some(keepalive) => Some(Mutex::new(spawn_keepalive(&inner, keepalive)?)),
+            None => None,
+        };
+
+        Ok(Self {
+            inner,
+            _keepalive: keepalive,
+        })
     }
 }
 
@@ -183,9 +584,30 @@ pub trait SonicChannel {
     /// Sonic channel struct
     type Channel;
 
+    /// Sonic server mode in which the channel is started.
+    const MODE: ChannelMode;
+
     /// Returns reference for sonic stream of connection
     fn stream(&self) -> &SonicStream;
 
+    /// Returns information about the sonic server. See [`ServerInfo`] for more
+    /// information.
+    fn server_info(&self) -> ServerInfo {
+        self.stream().server_info()
+    }
+
+    /// Pings the server and returns the round-trip latency with information
+    /// about the server. See [`HealthCheck`] for more information.
+    fn health_check(&self) -> Result<HealthCheck> {
+        let started = Instant::now();
+        self.stream().run_command(PingCommand)?;
+        Ok(HealthCheck::new(started.elapsed(), self.server_info()))
+    }
+
+    /// Wraps the started sonic stream to the channel.
+    #[doc(hidden)]
+    fn from_stream(stream: SonicStream) -> Self::Channel;
+
     /// Connects to sonic backend and run start command.
     ///
     /// ```rust,no_run
@@ -201,12 +623,293 @@ pub trait SonicChannel {
     fn start<A, S>(addr: A, password: S) -> Result<Self::Channel>
     where
         A: ToSocketAddrs,
-        S: ToString;
+        S: ToString,
+    {
+        SonicStream::connect_with_start(Self::MODE, addr, password).map(Self::from_stream)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(any(feature = "search", feature = "ingest"))]
+    use std::io::Read;
+    #[cfg(any(feature = "search", feature = "ingest"))]
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// In-memory duplex which replays the server responses and records the
+    /// written commands.
+    #[cfg(any(feature = "search", feature = "ingest"))]
+    #[derive(Debug)]
+    struct MemoryTransport {
+        input: io::Cursor<Vec<u8>>,
+        output: Arc<Mutex<Vec<u8>>>,
+    }
+
+    #[cfg(any(feature = "search", feature = "ingest"))]
+    impl MemoryTransport {
+        fn new(input: &str, output: &Arc<Mutex<Vec<u8>>>) -> Self {
+            Self {
+                input: io::Cursor::new(input.as_bytes().to_vec()),
+                output: output.clone(),
+            }
+        }
+    }
+
+    #[cfg(any(feature = "search", feature = "ingest"))]
+    impl Read for MemoryTransport {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    #[cfg(any(feature = "search", feature = "ingest"))]
+    impl Write for MemoryTransport {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(any(feature = "search", feature = "ingest"))]
+    impl Transport for MemoryTransport {}
+
+    #[cfg(any(feature = "search", feature = "ingest"))]
+    const STARTED: &str = "CONNECTED <sonic-server v1.4.0>\r\n\
+        STARTED search protocol(1) buffer(20000)\r\n";
+
+    #[cfg(feature = "search")]
+    fn query() -> crate::commands::QueryCommand {
+        crate::commands::QueryCommand {
+            req: crate::QueryRequest::new(crate::Dest::col("search"), "Beef")
+                .lang(whatlang::Lang::Eng),
+        }
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn should_run_commands_over_custom_transport() {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let connector_output = output.clone();
+        let connector = move || {
+            let script = format!("{}PENDING a\r\nEVENT QUERY a obj:1 obj:2\r\n", STARTED);
+            Ok(MemoryTransport::new(&script, &connector_output))
+        };
+
+        let stream = SonicStream::connect_with_connector(
+            ChannelMode::Search,
+            Arc::new(connector),
+            "pass",
+            StreamOptions {
+                reconnect: ReconnectPolicy::disabled(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(stream.run_command(query()).unwrap(), vec!["obj:1", "obj:2"]);
+        assert_eq!(
+            String::from_utf8(output.lock().unwrap().clone()).unwrap(),
+            "START search pass\r\nQUERY search default \"Beef\" LANG(eng)\r\n"
+        );
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn should_check_health_with_ping() {
+        let (stream, output) = start_with_script("PONG\r\n");
+        let channel = SearchChannel::from_stream(stream);
+
+        let health = channel.health_check().unwrap();
+        assert_eq!(health.mode, ChannelMode::Search);
+        assert_eq!(
+            health.max_buffer_size,
+            channel.server_info().max_buffer_size
+        );
+        assert!(String::from_utf8(output.lock().unwrap().clone())
+            .unwrap()
+            .ends_with("PING\r\n"));
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn should_report_command_and_line_of_wrong_response() {
+        let (stream, _) = start_with_script("OK\r\n");
+
+        match stream.run_command(query()) {
+            Err(Error::WrongResponse { command, line }) => {
+                assert_eq!(command, Some("QUERY"));
+                assert_eq!(line, "OK");
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn should_report_closed_stream_as_read_error() {
+        let (stream, _) = start_with_script("");
+
+        match stream.run_command(query()) {
+            Err(Error::ReadStream { command, source }) => {
+                assert_eq!(command, Some("QUERY"));
+                assert_eq!(source.kind(), io::ErrorKind::UnexpectedEof)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(all(feature = "search", feature = "ingest"))]
+    #[test]
+    fn should_encode_object_ids_with_codec() {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let connector_output = output.clone();
+        let connector = move || {
+            let script = format!(
+                "{}RESULT 1\r\nPENDING a\r\nEVENT QUERY a tenant%2Farticle%2042 obj%3A1\r\n",
+                STARTED
+            );
+            Ok(MemoryTransport::new(&script, &connector_output))
+        };
+
+        let stream = SonicStream::connect_with_connector(
+            ChannelMode::Search,
+            Arc::new(connector),
+            "pass",
+            StreamOptions {
+                object_id_codec: Some(Arc::new(UrlSafeCodec)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let flush = crate::commands::FlushCommand {
+            req: crate::FlushRequest::object("search", "default", "tenant/article 42"),
+        };
+        assert_eq!(stream.run_command(flush).unwrap(), 1);
+        assert_eq!(
+            stream.run_command(query()).unwrap(),
+            vec!["tenant/article 42", "obj:1"]
+        );
+        assert!(String::from_utf8(output.lock().unwrap().clone())
+            .unwrap()
+            .contains("FLUSHO search default tenant%2Farticle%2042\r\n"));
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn should_return_undecodable_object_ids_as_is() {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let connector = move || {
+            let script = format!("{}PENDING a\r\nEVENT QUERY a obj%3A1 100%\r\n", STARTED);
+            Ok(MemoryTransport::new(&script, &output))
+        };
+
+        let stream = SonicStream::connect_with_connector(
+            ChannelMode::Search,
+            Arc::new(connector),
+            "pass",
+            StreamOptions {
+                object_id_codec: Some(Arc::new(UrlSafeCodec)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(stream.run_command(query()).unwrap(), vec!["obj:1", "100%"]);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn should_reconnect_with_connector() {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let connector_output = output.clone();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let connector_attempts = attempts.clone();
+        let connector = move || {
+            // The first connection is closed right after the start command.
+            let script = match connector_attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => String::from(STARTED),
+                _ => format!("{}PENDING a\r\nEVENT QUERY a obj:1\r\n", STARTED),
+            };
+            Ok(MemoryTransport::new(&script, &connector_output))
+        };
+
+        let stream = SonicStream::connect_with_connector(
+            ChannelMode::Search,
+            Arc::new(connector),
+            "pass",
+            StreamOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(stream.run_command(query()).unwrap(), vec!["obj:1"]);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "ingest")]
+    fn start_reconnecting(first_script: &'static str, script: &'static str) -> SonicStream {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let attempts = AtomicUsize::new(0);
+        let connector = move || {
+            let script = match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => format!("{}{}", STARTED, first_script),
+                _ => format!("{}{}", STARTED, script),
+            };
+            Ok(MemoryTransport::new(&script, &output))
+        };
+
+        SonicStream::connect_with_connector(
+            ChannelMode::Ingest,
+            Arc::new(connector),
+            "pass",
+            StreamOptions::default(),
+        )
+        .unwrap()
+    }
+
+    #[cfg(feature = "ingest")]
+    fn flush() -> crate::commands::FlushCommand {
+        crate::commands::FlushCommand {
+            req: crate::FlushRequest::object("search", "default", "obj:1"),
+        }
+    }
+
+    #[cfg(feature = "ingest")]
+    #[test]
+    fn should_retry_not_idempotent_command_ended_by_server() {
+        let stream = start_reconnecting("ENDED timeout\r\n", "RESULT 1\r\n");
+
+        assert_eq!(stream.run_command(flush()).unwrap(), 1);
+    }
+
+    #[cfg(feature = "ingest")]
+    #[test]
+    fn should_not_retry_not_idempotent_command_after_write() {
+        let stream = start_reconnecting("", "RESULT 1\r\nRESULT 1\r\n");
+
+        match stream.run_command(flush()) {
+            Err(Error::ReadStream { .. }) => {}
+            _ => unreachable!(),
+        }
+        assert_eq!(stream.run_command(flush()).unwrap(), 1);
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn channels_should_be_send_and_sync() {
+        assert_send_sync::<SonicStream>();
+        #[cfg(feature = "search")]
+        assert_send_sync::<SearchChannel>();
+        #[cfg(feature = "ingest")]
+        assert_send_sync::<IngestChannel>();
+        #[cfg(feature = "control")]
+        assert_send_sync::<ControlChannel>();
+    }
 
     #[test]
     fn format_channel_enums() {
@@ -217,4 +920,124 @@ mod tests {
         #[cfg(feature = "control")]
         assert_eq!(format!("{}", ChannelMode::Control), String::from("control"));
     }
+
+    #[cfg(feature = "search")]
+    fn start_with_options(
+        script: &str,
+        options: StreamOptions,
+    ) -> (SonicStream, Arc<Mutex<Vec<u8>>>) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let connector_output = output.clone();
+        let script = format!("{}{}", STARTED, script);
+        let connector = move || Ok(MemoryTransport::new(&script, &connector_output));
+
+        let stream = SonicStream::connect_with_connector(
+            ChannelMode::Search,
+            Arc::new(connector),
+            "pass",
+            options,
+        )
+        .unwrap();
+
+        (stream, output)
+    }
+
+    #[cfg(feature = "search")]
+    fn start_with_script(script: &str) -> (SonicStream, Arc<Mutex<Vec<u8>>>) {
+        start_with_options(
+            script,
+            StreamOptions {
+                reconnect: ReconnectPolicy::disabled(),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[cfg(feature = "search")]
+    fn start_with_keepalive(
+        script: &str,
+        keepalive: Keepalive,
+    ) -> (SonicStream, Arc<Mutex<Vec<u8>>>) {
+        start_with_options(
+            script,
+            StreamOptions {
+                reconnect: ReconnectPolicy::disabled(),
+                keepalive: Some(keepalive),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn should_ping_idle_connection() {
+        let errors = Arc::new(AtomicUsize::new(0));
+        let handler_errors = errors.clone();
+        let keepalive = Keepalive::new(Duration::from_millis(20)).on_error(move |_| {
+            handler_errors.fetch_add(1, Ordering::SeqCst);
+        });
+        let (_stream, output) = start_with_keepalive(&"PONG\r\n".repeat(100), keepalive);
+
+        thread::sleep(Duration::from_millis(100));
+
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        assert!(output.starts_with("START search pass\r\nPING\r\n"));
+        assert_eq!(errors.load(Ordering::SeqCst), 0);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn should_report_keepalive_errors() {
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let handler_errors = errors.clone();
+        let keepalive = Keepalive::new(Duration::from_millis(20)).on_error(move |err| {
+            handler_errors.lock().unwrap().push(err.to_string());
+        });
+        // The server closes the connection instead of the reply.
+        let (_stream, _output) = start_with_keepalive("", keepalive);
+
+        thread::sleep(Duration::from_millis(50));
+
+        let errors = errors.lock().unwrap();
+        assert!(!errors.is_empty());
+        assert_eq!(
+            errors[0],
+            Error::read_stream(closed_by_server())
+                .in_command("PING", "")
+                .to_string()
+        );
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn should_reject_zero_keepalive_interval() {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let connector = move || Ok(MemoryTransport::new(STARTED, &output));
+
+        let res = SonicStream::connect_with_connector(
+            ChannelMode::Search,
+            Arc::new(connector),
+            "pass",
+            StreamOptions {
+                keepalive: Some(Keepalive::new(Duration::ZERO)),
+                ..Default::default()
+            },
+        );
+        assert!(matches!(res, Err(Error::InvalidOptions(_))));
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn should_stop_keepalive_when_stream_is_dropped() {
+        let (stream, output) =
+            start_with_keepalive("PONG\r\n", Keepalive::new(Duration::from_millis(20)));
+        drop(stream);
+
+        thread::sleep(Duration::from_millis(60));
+
+        assert_eq!(
+            String::from_utf8(output.lock().unwrap().clone()).unwrap(),
+            "START search pass\r\n"
+        );
+    }
 }
@@ -0,0 +1,350 @@
+use super::{
+    ChannelMode, ConnectOptions, Connector, HostAddrs, Keepalive, ObjectIdCodec, ReconnectPolicy,
+    RecordingConnector, SonicChannel, SonicStream, StreamOptions, TcpConnector, TranscriptRecorder,
+};
+use crate::result::*;
+use std::io;
+use std::net::ToSocketAddrs;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone)]
+enum Target {
+    Hosts(Vec<String>),
+    Connector(Arc<dyn Connector>),
+}
+
+impl std::fmt::Debug for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Hosts(hosts) => f.debug_tuple("Hosts").field(hosts).finish(),
+            Target::Connector(_) => f.write_str("Connector"),
+        }
+    }
+}
+
+/// Builder of sonic channels with custom connection options.
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # use std::time::Duration;
+/// # fn main() -> result::Result<()> {
+/// let builder = ChannelBuilder::new("localhost:1491", "SecretPassword")
+///     .connect_timeout(Duration::from_secs(1))
+///     .read_timeout(Duration::from_secs(5))
+///     .reconnect(ReconnectPolicy::disabled());
+///
+/// let search_channel = builder.start::<SearchChannel>()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ChannelBuilder {
+    target: Target,
+    password: String,
+    options: StreamOptions,
+    mode: Option<ChannelMode>,
+    recorder: Option<Arc<TranscriptRecorder>>,
+    #[cfg(feature = "tls")]
+    tls: Option<super::TlsConfig>,
+}
+
+impl std::fmt::Debug for ChannelBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChannelBuilder")
+            .field("target", &self.target)
+            .field("options", &self.options)
+            .field("mode", &self.mode)
+            .field("recorder", &self.recorder.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ChannelBuilder {
+    /// Creates a new builder with default options.
+    pub fn new(addr: impl ToString, password: impl ToString) -> Self {
+        Self::with_target(Target::Hosts(vec![addr.to_string()]), password)
+    }
+
+    /// Creates a new builder from the connection options.
+    pub fn from_options(options: ConnectOptions) -> Self {
+        let mut builder = Self::with_target(Target::Hosts(options.hosts), options.password);
+        builder.mode = options.mode;
+        builder.options = StreamOptions {
+            reconnect: options.reconnect,
+            connect_timeout: options.connect_timeout,
+            read_timeout: options.read_timeout,
+            write_timeout: options.write_timeout,
+            keepalive: options.keepalive.map(Keepalive::new),
+            object_id_codec: None,
+        };
+        builder
+    }
+
+    /// Creates a new builder from the connection URL. See [`ConnectOptions`]
+    /// for the format.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # fn main() -> result::Result<()> {
+    /// let ingest_channel: IngestChannel =
+    ///     ChannelBuilder::from_url("sonic://:SecretPassword@localhost:1491/ingest")?.start()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_url(url: &str) -> Result<Self> {
+        ConnectOptions::parse(url).map(Self::from_options)
+    }
+
+    /// Creates a new builder from the environment variables. See
+    /// [`ConnectOptions::from_env`] for the list of variables.
+    pub fn from_env() -> Result<Self> {
+        ConnectOptions::from_env().map(Self::from_options)
+    }
+
+    /// Creates a new builder which opens connections with the custom connector.
+    ///
+    /// The timeouts and TLS options of the builder are applied to TCP connections
+    /// only, so the connector should configure its transport by itself.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # fn main() -> result::Result<()> {
+    /// # #[cfg(unix)]
+    /// # {
+    /// use std::os::unix::net::UnixStream;
+    ///
+    /// let search_channel: SearchChannel = ChannelBuilder::with_connector(
+    ///     || UnixStream::connect("/run/sonic.sock"),
+    ///     "SecretPassword",
+    /// )
+    /// .start()?;
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_connector(connector: impl Connector + 'static, password: impl ToString) -> Self {
+        Self::with_target(Target::Connector(Arc::new(connector)), password)
+    }
+
+    fn with_target(target: Target, password: impl ToString) -> Self {
+        Self {
+            target,
+            password: password.to_string(),
+            options: StreamOptions::default(),
+            mode: None,
+            recorder: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+
+    /// Set the reconnect policy of the channel.
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.options.reconnect = policy;
+        self
+    }
+
+    /// Set the timeout of establishing the TCP connection. By default, the timeout
+    /// of the operating system is used.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.options.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the deadline for reading every response line from the server.
+    ///
+    /// If the server doesn't respond in time, the command fails with the
+    /// [`Error::Timeout`](crate::result::Error::Timeout) error and the connection is
+    /// closed, because the late response cannot be paired with the next command.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.options.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the deadline for writing every command to the server.
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.options.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Ping the idle connection in the background. See [`Keepalive`] for more
+    /// information.
+    pub fn keepalive(mut self, keepalive: Keepalive) -> Self {
+        self.options.keepalive = Some(keepalive);
+        self
+    }
+
+    /// Encode the object ids of the requests and decode the object ids of the
+    /// query results. See [`ObjectIdCodec`] for more information.
+    ///
+    /// The async channels aren't started by the builder and the multiplexed
+    /// search channel doesn't support the codec, so they never use it. Don't mix them with the
+    /// channels of this builder on the same collection.
+    pub fn object_id_codec(mut self, codec: impl ObjectIdCodec + 'static) -> Self {
+        self.options.object_id_codec = Some(Arc::new(codec));
+        self
+    }
+
+    /// Record the lines exchanged with the server to the transcript. See
+    /// [`TranscriptRecorder`] for more information.
+    pub fn record(mut self, recorder: TranscriptRecorder) -> Self {
+        self.recorder = Some(Arc::new(recorder));
+        self
+    }
+
+    /// Connect to sonic server over TLS. See [`TlsConfig`](super::TlsConfig) for
+    /// more information.
+    ///
+    /// **Note:** This requires enabling the `tls` feature.
+    #[cfg(feature = "tls")]
+    pub fn tls(mut self, config: super::TlsConfig) -> Self {
+        self.tls = Some(config);
+        self
+    }
+
+    /// Connects to sonic backend and run start command in the mode of the channel.
+    ///
+    /// If the mode was set in the connection options, it must be the mode of the
+    /// channel.
+    pub fn start<C>(&self) -> Result<C>
+    where
+        C: SonicChannel<Channel = C>,
+    {
+        if let Some(mode) = self.mode {
+            if mode != C::MODE {
+                return Err(Error::InvalidOptions(format!(
+                    "cannot start {} channel in {} mode",
+                    C::MODE,
+                    mode
+                )));
+            }
+        }
+
+        self.connect(C::MODE, self.options.clone())
+            .map(C::from_stream)
+    }
+
+    /// Connects to sonic backend and starts the
+    /// [`MultiplexedSearchChannel`](super::MultiplexedSearchChannel) with the
+    /// options of the builder.
+    ///
+    /// The read timeout limits how long each command waits for its reply. The
+    /// channel doesn't reconnect and doesn't send keepalive `PING`, and the TLS
+    /// connections cannot be multiplexed, because the stream is split between
+    /// the reader and writer.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # use std::time::Duration;
+    /// # fn main() -> result::Result<()> {
+    /// let channel = ChannelBuilder::new("localhost:1491", "SecretPassword")
+    ///     .read_timeout(Duration::from_secs(5))
+    ///     .start_multiplexed()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "search")]
+    pub fn start_multiplexed(&self) -> Result<super::MultiplexedSearchChannel> {
+        if let Some(mode) = self.mode {
+            if mode != ChannelMode::Search {
+                return Err(Error::InvalidOptions(format!(
+                    "cannot start {} channel in {} mode",
+                    ChannelMode::Search,
+                    mode
+                )));
+            }
+        }
+
+        let options = StreamOptions {
+            reconnect: ReconnectPolicy::disabled(),
+            keepalive: None,
+            ..self.options.clone()
+        };
+        self.connect(ChannelMode::Search, options)
+            .and_then(|stream| {
+                super::MultiplexedSearchChannel::from_stream(stream, self.options.read_timeout)
+            })
+    }
+
+    fn connect(&self, mode: ChannelMode, options: StreamOptions) -> Result<SonicStream> {
+        let connector: Arc<dyn Connector> = match &self.target {
+            Target::Hosts(hosts) => Arc::new(TcpConnector {
+                hosts: self.resolve(hosts)?,
+                options: options.clone(),
+            }),
+            Target::Connector(connector) => connector.clone(),
+        };
+        let connector = match &self.recorder {
+            Some(recorder) => Arc::new(RecordingConnector {
+                connector,
+                recorder: recorder.clone(),
+            }),
+            None => connector,
+        };
+
+        SonicStream::connect_with_connector(mode, connector, &self.password, options)
+    }
+
+    /// Resolves addresses of the hosts. Hosts which cannot be resolved are skipped.
+    fn resolve(&self, hosts: &[String]) -> Result<Vec<HostAddrs>> {
+        let mut resolved = Vec::with_capacity(hosts.len());
+        let mut last_err = None;
+        for addr in hosts {
+            let addrs = match addr.to_socket_addrs() {
+                Ok(addrs) => addrs.collect(),
+                Err(err) => {
+                    log::warn!("[channel] cannot resolve {}: {}", addr, err);
+                    last_err = Some(err);
+                    continue;
+                }
+            };
+
+            #[allow(unused_mut)]
+            let mut host = HostAddrs::new(addrs);
+            #[cfg(feature = "tls")]
+            if let Some(tls) = &self.tls {
+                host.tls = Some(tls.connector(host_name(addr))?);
+            }
+            resolved.push(host);
+        }
+
+        if resolved.is_empty() {
+            let err = last_err.unwrap_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "no hosts to connect")
+            });
+            Err(Error::ConnectToServer(err))
+        } else {
+            Ok(resolved)
+        }
+    }
+}
+
+/// Returns the host part of the `host:port` address.
+#[cfg(feature = "tls")]
+fn host_name(addr: &str) -> &str {
+    if let Some(rest) = addr.strip_prefix('[') {
+        if let Some((host, _)) = rest.split_once(']') {
+            return host;
+        }
+    }
+
+    match addr.rsplit_once(':') {
+        Some((host, _)) if !host.contains(':') => host,
+        _ => addr,
+    }
+}
+
+#[cfg(all(test, feature = "tls"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_extract_host_from_address() {
+        assert_eq!(host_name("localhost:1491"), "localhost");
+        assert_eq!(host_name("sonic.example.com"), "sonic.example.com");
+        assert_eq!(host_name("127.0.0.1:1491"), "127.0.0.1");
+        assert_eq!(host_name("[::1]:1491"), "::1");
+        assert_eq!(host_name("::1"), "::1");
+    }
+}
@@ -0,0 +1,117 @@
+use crate::result::*;
+
+/// Reversible encoding of the object ids.
+///
+/// Sonic takes the object id as a single command argument, so composite keys
+/// like `tenant/article 42` cannot be sent verbatim. The channel encodes the
+/// object id of the `push`, `pop`, `flush` and `count` requests and decodes the
+/// object ids found by the `query` command, so the caller always works with
+/// the original keys. Found object ids which cannot be decoded (e.g. pushed
+/// without the codec) are returned as is.
+///
+/// The codec is set by the [`ChannelBuilder`](crate::ChannelBuilder), so it's
+/// applied to the channels started by the builder only. The async channels
+/// and the `MultiplexedSearchChannel` send and return the object ids
+/// verbatim.
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # fn main() -> result::Result<()> {
+/// let ingest_channel: IngestChannel = ChannelBuilder::new("localhost:1491", "SecretPassword")
+///     .object_id_codec(UrlSafeCodec)
+///     .start()?;
+///
+/// let dest = Dest::col("articles").obj("tenant/article 42");
+/// ingest_channel.push(PushRequest::new(dest, "Sweet Teriyaki Beef Skewers"))?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait ObjectIdCodec: Send + Sync + std::fmt::Debug {
+    /// Encodes the object id before it's sent to the server.
+    fn encode(&self, object_id: &str) -> String;
+
+    /// Decodes the object id received from the server.
+    fn decode(&self, encoded: &str) -> Result<String>;
+}
+
+/// Percent-encoding of all characters except the URL-safe ones (`A-Z`, `a-z`,
+/// `0-9`, `-`, `.`, `_`, `~`).
+///
+/// The encoded object id is always a valid identifier unless it's too long.
+///
+/// ```rust
+/// # use sonic_channel::*;
+/// let encoded = UrlSafeCodec.encode("tenant/article 42");
+/// assert_eq!(encoded, "tenant%2Farticle%2042");
+/// assert_eq!(UrlSafeCodec.decode(&encoded).unwrap(), "tenant/article 42");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UrlSafeCodec;
+
+impl ObjectIdCodec for UrlSafeCodec {
+    fn encode(&self, object_id: &str) -> String {
+        let mut encoded = String::with_capacity(object_id.len());
+        for byte in object_id.bytes() {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                encoded.push(char::from(byte));
+            } else {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+        encoded
+    }
+
+    fn decode(&self, encoded: &str) -> Result<String> {
+        let invalid =
+            || Error::InvalidIdentifier(format!("object id `{}` cannot be decoded", encoded));
+
+        let mut bytes = Vec::with_capacity(encoded.len());
+        let mut rest = encoded.as_bytes();
+        while let Some((&byte, tail)) = rest.split_first() {
+            if byte == b'%' {
+                let hex = tail.get(..2).ok_or_else(invalid)?;
+                let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+                rest = &tail[2..];
+            } else {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+
+        String::from_utf8(bytes).map_err(|_| invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identifier::validate_identifier;
+
+    #[test]
+    fn should_encode_composite_keys() {
+        for key in ["tenant/article 42", "\"quoted\"", "статья:1", "a%b", ""] {
+            let encoded = UrlSafeCodec.encode(key);
+            assert_eq!(UrlSafeCodec.decode(&encoded).unwrap(), key);
+            if !key.is_empty() {
+                assert!(validate_identifier("object id", &encoded).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn should_keep_unreserved_characters() {
+        assert_eq!(UrlSafeCodec.encode("article-1.v2_a~b"), "article-1.v2_a~b");
+        assert_eq!(UrlSafeCodec.decode("article-1").unwrap(), "article-1");
+    }
+
+    #[test]
+    fn should_reject_malformed_encoding() {
+        for encoded in ["%", "%2", "%zz", "%FF"] {
+            match UrlSafeCodec.decode(encoded) {
+                Err(Error::InvalidIdentifier(_)) => {}
+                _ => unreachable!(),
+            }
+        }
+    }
+}
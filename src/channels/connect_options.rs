@@ -0,0 +1,403 @@
+use super::{ChannelMode, ReconnectPolicy};
+use crate::result::*;
+use std::str::FromStr;
+use std::time::Duration;
+
+const SCHEME: &str = "sonic://";
+const DEFAULT_PORT: u16 = 1491;
+
+/// Connection options which can be parsed from the URL or loaded from the
+/// environment variables.
+///
+/// The URL has the following format:
+///
+/// ```text
+/// sonic://:password@host[:port][,host2[:port2]...][/mode][?option=value&...]
+/// ```
+///
+/// The default port is `1491`. If several hosts are given, the channel connects
+/// to the first available host, including reconnects.
+///
+/// Supported options:
+///
+/// - `mode` - `search`, `ingest` or `control`. Same as the path of the URL.
+/// - `connect_timeout`, `read_timeout`, `write_timeout` - duration like `500ms`,
+///   `5s` or `1m`. Numbers without the unit are seconds. Zero isn't allowed.
+/// - `keepalive` - interval of the [`Keepalive`](super::Keepalive) pings, greater
+///   than zero.
+/// - `reconnect` - `true` or `false`.
+/// - `reconnect_attempts` - the number of reconnect attempts.
+/// - `reconnect_backoff` - delay between reconnect attempts, which can be zero.
+///
+/// Special characters of the password should be percent-encoded.
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # fn main() -> result::Result<()> {
+/// let options: ConnectOptions =
+///     "sonic://:SecretPassword@sonic-1:1491,sonic-2:1491/search?connect_timeout=5s".parse()?;
+/// let search_channel: SearchChannel = ChannelBuilder::from_options(options).start()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct ConnectOptions {
+    /// Addresses of the sonic servers in the `host:port` format.
+    pub hosts: Vec<String>,
+    /// Password of the sonic server.
+    pub password: String,
+    /// The mode of the channel. If it's set, only the channel of this mode can be started.
+    pub mode: Option<ChannelMode>,
+    /// Timeout of establishing the TCP connection.
+    pub connect_timeout: Option<Duration>,
+    /// Deadline for reading every response line.
+    pub read_timeout: Option<Duration>,
+    /// Deadline for writing every command.
+    pub write_timeout: Option<Duration>,
+    /// Interval of the keepalive pings.
+    pub keepalive: Option<Duration>,
+    /// The reconnect policy of the channel.
+    pub reconnect: ReconnectPolicy,
+}
+
+impl std::fmt::Debug for ConnectOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectOptions")
+            .field("hosts", &self.hosts)
+            .field("mode", &self.mode)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("write_timeout", &self.write_timeout)
+            .field("keepalive", &self.keepalive)
+            .field("reconnect", &self.reconnect)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConnectOptions {
+    /// Creates options with a single host and default settings.
+    pub fn new(addr: impl ToString, password: impl ToString) -> Self {
+        Self {
+            hosts: vec![addr.to_string()],
+            password: password.to_string(),
+            mode: None,
+            connect_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            keepalive: None,
+            reconnect: ReconnectPolicy::default(),
+        }
+    }
+
+    /// Parses the connection URL. See [`ConnectOptions`] for the format.
+    pub fn parse(url: &str) -> Result<Self> {
+        let rest = url
+            .strip_prefix(SCHEME)
+            .ok_or_else(|| invalid(format!("URL must start with `{}`", SCHEME)))?;
+
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None),
+        };
+        let (authority, path) = match rest.split_once('/') {
+            Some((authority, path)) => (authority, path),
+            None => (rest, ""),
+        };
+        let (password, hosts) = match authority.rsplit_once('@') {
+            // The password may be given as the user name or as the password.
+            Some((user_info, hosts)) => match user_info.split_once(':') {
+                Some((_, password)) => (decode(password)?, hosts),
+                None => (decode(user_info)?, hosts),
+            },
+            None => (String::new(), authority),
+        };
+
+        let hosts = hosts
+            .split(',')
+            .map(with_default_port)
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut options = Self {
+            hosts,
+            ..Self::new("", password)
+        };
+        if !path.is_empty() {
+            options.set("mode", &decode(path)?)?;
+        }
+        for pair in query.into_iter().flat_map(|q| q.split('&')) {
+            if pair.is_empty() {
+                continue;
+            }
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            options.set(key, &decode(value)?)?;
+        }
+
+        Ok(options)
+    }
+
+    /// Loads options from the environment variables.
+    ///
+    /// If `SONIC_URL` is set, it's parsed as the connection URL. Otherwise, the
+    /// hosts are read from the comma separated `SONIC_HOSTS` variable, the password
+    /// from `SONIC_PASSWORD`, and every other option from the variable with the
+    /// upper case name of the option and `SONIC_` prefix (e.g. `SONIC_MODE`,
+    /// `SONIC_CONNECT_TIMEOUT`).
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        if let Some(url) = var("SONIC_URL") {
+            return Self::parse(&url);
+        }
+
+        let hosts = var("SONIC_HOSTS").ok_or_else(|| invalid("SONIC_HOSTS is not set"))?;
+        let hosts = hosts
+            .split(',')
+            .map(|host| with_default_port(host.trim()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut options = Self {
+            hosts,
+            ..Self::new("", var("SONIC_PASSWORD").unwrap_or_default())
+        };
+        for key in OPTIONS {
+            if let Some(value) = var(&format!("SONIC_{}", key.to_uppercase())) {
+                options.set(key, &value)?;
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "mode" => self.mode = Some(parse_mode(value)?),
+            "connect_timeout" => self.connect_timeout = Some(parse_duration(key, value)?),
+            "read_timeout" => self.read_timeout = Some(parse_duration(key, value)?),
+            "write_timeout" => self.write_timeout = Some(parse_duration(key, value)?),
+            "keepalive" => self.keepalive = Some(parse_duration(key, value)?),
+            "reconnect" => {
+                self.reconnect.enabled = match value {
+                    "true" | "1" => true,
+                    "false" | "0" => false,
+                    _ => return Err(invalid(format!("invalid value of `{}`", key))),
+                }
+            }
+            "reconnect_attempts" => {
+                self.reconnect.max_attempts = value
+                    .parse()
+                    .map_err(|_| invalid(format!("invalid value of `{}`", key)))?;
+            }
+            "reconnect_backoff" => self.reconnect.backoff = parse_delay(key, value)?,
+            _ => return Err(invalid(format!("unknown option `{}`", key))),
+        }
+        Ok(())
+    }
+}
+
+/// Options which can be set in the URL query or the environment variables.
+const OPTIONS: [&str; 8] = [
+    "mode",
+    "connect_timeout",
+    "read_timeout",
+    "write_timeout",
+    "keepalive",
+    "reconnect",
+    "reconnect_attempts",
+    "reconnect_backoff",
+];
+
+impl FromStr for ConnectOptions {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+fn invalid(message: impl ToString) -> Error {
+    Error::InvalidOptions(message.to_string())
+}
+
+fn with_default_port(host: &str) -> Result<String> {
+    if host.is_empty() {
+        return Err(invalid("empty host"));
+    }
+
+    let has_port = match host.strip_prefix('[') {
+        // IPv6 address, e.g. `[::1]:1491`.
+        Some(rest) => rest.contains("]:"),
+        None => host.contains(':'),
+    };
+    if has_port {
+        Ok(host.to_string())
+    } else {
+        Ok(format!("{}:{}", host, DEFAULT_PORT))
+    }
+}
+
+fn parse_mode(value: &str) -> Result<ChannelMode> {
+    match value {
+        #[cfg(feature = "search")]
+        "search" => Ok(ChannelMode::Search),
+        #[cfg(feature = "ingest")]
+        "ingest" => Ok(ChannelMode::Ingest),
+        #[cfg(feature = "control")]
+        "control" => Ok(ChannelMode::Control),
+        _ => Err(invalid(format!("unsupported mode `{}`", value))),
+    }
+}
+
+/// Parses the duration of the timeout or interval, which cannot be zero.
+fn parse_duration(key: &str, value: &str) -> Result<Duration> {
+    let duration = parse_delay(key, value)?;
+    if duration.is_zero() {
+        return Err(invalid(format!("zero duration of `{}`", key)));
+    }
+    Ok(duration)
+}
+
+/// Parses the duration, including zero.
+fn parse_delay(key: &str, value: &str) -> Result<Duration> {
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => value.split_at(i),
+        None => (value, "s"),
+    };
+    let number = number
+        .parse::<u64>()
+        .map_err(|_| invalid(format!("invalid duration of `{}`", key)))?;
+
+    match unit {
+        "ms" => Ok(Duration::from_millis(number)),
+        "s" => Ok(Duration::from_secs(number)),
+        "m" => number
+            .checked_mul(60)
+            .map(Duration::from_secs)
+            .ok_or_else(|| invalid(format!("too long duration of `{}`", key))),
+        _ => Err(invalid(format!("invalid duration unit of `{}`", key))),
+    }
+}
+
+/// Decodes percent-encoded characters.
+fn decode(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = value
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| invalid("invalid percent-encoding"))?;
+            res.push(byte);
+            i += 3;
+        } else {
+            res.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(res).map_err(|_| invalid("invalid percent-encoding"))
+}
+
+#[cfg(all(test, feature = "search"))]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn should_parse_url() {
+        let options = ConnectOptions::parse(
+            "sonic://:Secret%40Password@sonic-1,sonic-2:1492,[::1]/search?connect_timeout=500ms&read_timeout=5&reconnect=false",
+        )
+        .unwrap();
+
+        assert_eq!(
+            options.hosts,
+            vec!["sonic-1:1491", "sonic-2:1492", "[::1]:1491"]
+        );
+        assert_eq!(options.password, "Secret@Password");
+        assert_eq!(options.mode, Some(ChannelMode::Search));
+        assert_eq!(options.connect_timeout, Some(Duration::from_millis(500)));
+        assert_eq!(options.read_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(options.write_timeout, None);
+        assert!(!options.reconnect.enabled);
+    }
+
+    #[test]
+    fn should_parse_password_as_user_name() {
+        let options =
+            ConnectOptions::parse("sonic://SecretPassword@localhost?mode=search").unwrap();
+        assert_eq!(options.hosts, vec!["localhost:1491"]);
+        assert_eq!(options.password, "SecretPassword");
+        assert_eq!(options.mode, Some(ChannelMode::Search));
+    }
+
+    #[test]
+    fn should_reject_invalid_url() {
+        for url in [
+            "http://localhost:1491",
+            "sonic://:pass@",
+            "sonic://:pass@localhost/unknown",
+            "sonic://:pass@localhost?connect_timeout=5h",
+            "sonic://:pass@localhost?keepalive=0",
+            "sonic://:pass@localhost?read_timeout=0ms",
+            "sonic://:pass@localhost?write_timeout=0s",
+            "sonic://:pass@localhost?unknown=1",
+            "sonic://:pass%2@localhost",
+        ] {
+            match ConnectOptions::parse(url) {
+                Err(Error::InvalidOptions(_)) => {}
+                _ => unreachable!("{}", url),
+            }
+        }
+    }
+
+    #[test]
+    fn should_allow_zero_reconnect_backoff() {
+        let options = ConnectOptions::parse("sonic://:pass@localhost?reconnect_backoff=0").unwrap();
+        assert_eq!(options.reconnect.backoff, Duration::ZERO);
+    }
+
+    #[test]
+    fn should_reject_overflowing_duration() {
+        assert_eq!(
+            parse_duration("keepalive", "307445734561825860m").unwrap(),
+            Duration::from_secs(u64::MAX - 15)
+        );
+        match parse_duration("keepalive", "307445734561825861m") {
+            Err(Error::InvalidOptions(_)) => {}
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn should_load_options_from_vars() {
+        let vars = HashMap::from([
+            ("SONIC_HOSTS", "sonic-1:1491, sonic-2"),
+            ("SONIC_PASSWORD", "SecretPassword"),
+            ("SONIC_MODE", "search"),
+            ("SONIC_KEEPALIVE", "1m"),
+            ("SONIC_RECONNECT_ATTEMPTS", "5"),
+        ]);
+        let options =
+            ConnectOptions::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+
+        assert_eq!(options.hosts, vec!["sonic-1:1491", "sonic-2:1491"]);
+        assert_eq!(options.password, "SecretPassword");
+        assert_eq!(options.mode, Some(ChannelMode::Search));
+        assert_eq!(options.keepalive, Some(Duration::from_secs(60)));
+        assert_eq!(options.reconnect.max_attempts, 5);
+    }
+
+    #[test]
+    fn should_prefer_url_from_vars() {
+        let options = ConnectOptions::from_vars(|name| match name {
+            "SONIC_URL" => Some(String::from("sonic://:pass@localhost:1491")),
+            _ => Some(String::from("ignored")),
+        })
+        .unwrap();
+
+        assert_eq!(options, ConnectOptions::new("localhost:1491", "pass"));
+    }
+}
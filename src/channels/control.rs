@@ -1,7 +1,6 @@
 use super::{ChannelMode, SonicChannel, SonicStream};
 use crate::commands::*;
 use crate::result::Result;
-use std::net::ToSocketAddrs;
 
 /// The Sonic Channel Control mode is used for administration purposes.
 /// Once in this mode, you cannot switch to other modes or gain access
@@ -9,8 +8,8 @@ use std::net::ToSocketAddrs;
 ///
 /// ### Available commands
 ///
-/// In this mode you can use `consolidate`, `backup`, `restore`,
-/// `ping` and `quit` commands.
+/// In this mode you can use `consolidate`, `backup`, `restore`, `info`,
+/// `help`, `ping` and `quit` commands.
 ///
 /// **Note:** This mode requires enabling the `control` feature.
 #[derive(Debug)]
@@ -19,16 +18,14 @@ pub struct ControlChannel(SonicStream);
 impl SonicChannel for ControlChannel {
     type Channel = ControlChannel;
 
+    const MODE: ChannelMode = ChannelMode::Control;
+
     fn stream(&self) -> &SonicStream {
         &self.0
     }
 
-    fn start<A, S>(addr: A, password: S) -> Result<Self::Channel>
-    where
-        A: ToSocketAddrs,
-        S: ToString,
-    {
-        SonicStream::connect_with_start(ChannelMode::Control, addr, password).map(Self)
+    fn from_stream(stream: SonicStream) -> Self::Channel {
+        Self(stream)
     }
 }
 
@@ -66,6 +63,47 @@ impl ControlChannel {
         /// # }
         use PingCommand for fn ping();
     );
+
+    init_command!(
+        /// Show the manual of the server, e.g. the list of available commands.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # fn main() -> result::Result<()> {
+        /// let channel = ControlChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// )?;
+        ///
+        /// let manuals = channel.help(HelpRequest::Manuals)?;
+        /// dbg!(manuals.entries);
+        /// # Ok(())
+        /// # }
+        use HelpCommand<'_> for fn help(
+            req: HelpRequest<'_>,
+        )
+    );
+
+    /// Returns the commands available in the channel mode.
+    ///
+    /// It can be used to detect the capabilities of the server.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # fn main() -> result::Result<()> {
+    /// let channel = ControlChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// )?;
+    ///
+    /// let commands = channel.commands()?;
+    /// assert!(commands.contains(&String::from("PING")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn commands(&self) -> Result<Vec<String>> {
+        self.help(HelpRequest::commands()).map(|res| res.entries)
+    }
 }
 
 impl ControlChannel {
@@ -86,11 +124,33 @@ impl ControlChannel {
         /// control_channel.trigger(TriggerRequest::Consolidate)?;
         /// # Ok(())
         /// # }
-        use TriggerCommand for fn trigger(
-            req: TriggerRequest,
+        use TriggerCommand<'_> for fn trigger(
+            req: TriggerRequest<'_>,
         )
     );
 
+    init_command!(
+        /// Get the statistics of the server, e.g. uptime, connected clients
+        /// and command latency.
+        ///
+        /// Note: This method requires enabling the `control` feature and start
+        /// connection in Control mode.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # fn main() -> result::Result<()> {
+        /// let control_channel = ControlChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// )?;
+        ///
+        /// let stats = control_channel.info()?;
+        /// println!("uptime: {:?}", stats.uptime);
+        /// # Ok(())
+        /// # }
+        use InfoCommand for fn info();
+    );
+
     /// Consolidate indexed search data instead of waiting for the next automated
     /// consolidation tick.
     ///
@@ -1,7 +1,6 @@
 use super::{ChannelMode, SonicChannel, SonicStream};
 use crate::commands::*;
 use crate::result::Result;
-use std::net::ToSocketAddrs;
 
 /// The Sonic Channel Ingest mode is used for altering the search index
 /// (push, pop and flush). Once in this mode, you cannot switch to other
@@ -10,7 +9,7 @@ use std::net::ToSocketAddrs;
 /// ### Available commands
 ///
 /// In this mode you can use `push`, `pop`, `flushc`, `flushb`, `flusho`,
-/// `bucket_count`, `object_count`, `word_count`, `ping` and `quit` commands.
+/// `bucket_count`, `object_count`, `word_count`, `help`, `ping` and `quit` commands.
 ///
 /// **Note:** This mode requires enabling the `ingest` feature.
 #[derive(Debug)]
@@ -19,16 +18,14 @@ pub struct IngestChannel(SonicStream);
 impl SonicChannel for IngestChannel {
     type Channel = IngestChannel;
 
+    const MODE: ChannelMode = ChannelMode::Ingest;
+
     fn stream(&self) -> &SonicStream {
         &self.0
     }
 
-    fn start<A, S>(addr: A, password: S) -> Result<Self::Channel>
-    where
-        A: ToSocketAddrs,
-        S: ToString,
-    {
-        SonicStream::connect_with_start(ChannelMode::Ingest, addr, password).map(Self)
+    fn from_stream(stream: SonicStream) -> Self::Channel {
+        Self(stream)
     }
 }
 
@@ -66,60 +63,105 @@ impl IngestChannel {
         /// # }
         use PingCommand for fn ping();
     );
-}
 
-impl IngestChannel {
     init_command!(
-        /// Push search data in the index.
-        ///
-        /// Note: This method requires enabling the `ingest` feature and start
-        /// connection in Ingest mode.
+        /// Show the manual of the server, e.g. the list of available commands.
         ///
         /// ```rust,no_run
         /// # use sonic_channel::*;
         /// # fn main() -> result::Result<()> {
-        /// let ingest_channel = IngestChannel::start(
+        /// let channel = IngestChannel::start(
         ///     "localhost:1491",
         ///     "SecretPassword",
         /// )?;
         ///
-        /// let result = ingest_channel.push(PushRequest::new(
-        ///     Dest::col("search").obj("recipe:295"),
-        ///     "Sweet Teriyaki Beef Skewers"
-        /// ))?;
-        /// assert_eq!(result, ());
+        /// let manuals = channel.help(HelpRequest::Manuals)?;
+        /// dbg!(manuals.entries);
         /// # Ok(())
         /// # }
-        /// ```
-        use PushCommand for fn push<'a>(
-            req: PushRequest,
-        );
+        use HelpCommand<'_> for fn help(
+            req: HelpRequest<'_>,
+        )
     );
 
-    init_command!(
-        /// Pop search data from the index. Returns removed words count as usize type.
-        ///
-        /// Note: This method requires enabling the `ingest` feature and start
-        /// connection in Ingest mode.
-        ///
-        /// ```rust,no_run
-        /// # use sonic_channel::*;
-        /// # fn main() -> result::Result<()> {
-        /// let ingest_channel = IngestChannel::start(
-        ///     "localhost:1491",
-        ///     "SecretPassword",
-        /// )?;
-        ///
-        /// let dest = Dest::col("search").obj("recipe:295");
-        /// let result = ingest_channel.pop(PopRequest::new(dest, "beef"))?;
-        /// assert_eq!(result, 1);
-        /// # Ok(())
-        /// # }
-        /// ```
-        use PopCommand for fn pop(
-            req: PopRequest,
-        );
-    );
+    /// Returns the commands available in the channel mode.
+    ///
+    /// It can be used to detect the capabilities of the server.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # fn main() -> result::Result<()> {
+    /// let channel = IngestChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// )?;
+    ///
+    /// let commands = channel.commands()?;
+    /// assert!(commands.contains(&String::from("PING")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn commands(&self) -> Result<Vec<String>> {
+        self.help(HelpRequest::commands()).map(|res| res.entries)
+    }
+}
+
+impl IngestChannel {
+    /// Push search data in the index. Returns the count of sent push commands.
+    ///
+    /// If the request doesn't fit the buffer size of the server, the text is
+    /// split on words and pushed by several commands.
+    ///
+    /// Note: This method requires enabling the `ingest` feature and start
+    /// connection in Ingest mode.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # fn main() -> result::Result<()> {
+    /// let ingest_channel = IngestChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// )?;
+    ///
+    /// let result = ingest_channel.push(PushRequest::new(
+    ///     Dest::col("search").obj("recipe:295"),
+    ///     "Sweet Teriyaki Beef Skewers"
+    /// ))?;
+    /// assert_eq!(result, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn push(&self, req: PushRequest) -> Result<usize> {
+        let chunks = self.stream().run_split_command(PushCommand { req })?;
+        Ok(chunks.len())
+    }
+
+    /// Pop search data from the index. Returns removed words count as usize type.
+    ///
+    /// If the request doesn't fit the buffer size of the server, the text is
+    /// split on words and popped by several commands.
+    ///
+    /// Note: This method requires enabling the `ingest` feature and start
+    /// connection in Ingest mode.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # fn main() -> result::Result<()> {
+    /// let ingest_channel = IngestChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// )?;
+    ///
+    /// let dest = Dest::col("search").obj("recipe:295");
+    /// let result = ingest_channel.pop(PopRequest::new(dest, "beef"))?;
+    /// assert_eq!(result, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pop(&self, req: PopRequest) -> Result<usize> {
+        let counts = self.stream().run_split_command(PopCommand { req })?;
+        Ok(counts.into_iter().sum())
+    }
 
     init_command!(
         /// Flush all indexed data from collections.
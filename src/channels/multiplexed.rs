@@ -0,0 +1,508 @@
+use super::{closed_by_server, ChannelMode, HealthCheck, ServerInfo, SonicStream, Transport};
+use crate::commands::*;
+use crate::protocol::{self, EventId, Protocol};
+use crate::result::*;
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, Write};
+use std::net::ToSocketAddrs;
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+type Reply = mpsc::Sender<Result<protocol::Response>>;
+
+/// Routes sonic responses back to the callers which are waiting for them.
+///
+/// Sonic replies to every command in order, but the `QUERY`, `SUGGEST` and `LIST`
+/// commands are answered with `PENDING <id>` first and the result comes later as
+/// `EVENT <kind> <id> ...`, so there is no need to wait for the event before
+/// sending the next command.
+#[derive(Debug, Default)]
+struct Dispatcher {
+    /// Callers waiting for the first reply, in the order the commands were sent.
+    queued: VecDeque<Reply>,
+    /// Callers waiting for the event with a known id.
+    pending: HashMap<EventId, Reply>,
+    /// The connection is broken and new commands should not be sent.
+    closed: bool,
+}
+
+impl Dispatcher {
+    fn dispatch(&mut self, res: Result<protocol::Response>) {
+        match res {
+            Ok(protocol::Response::Event(kind, event_id, objects)) => {
+                if let Some(reply) = self.pending.remove(&event_id) {
+                    let _ = reply.send(Ok(protocol::Response::Event(kind, event_id, objects)));
+                } else {
+                    log::warn!("[channel] received event with unknown id {}", event_id);
+                }
+            }
+            Ok(protocol::Response::Pending(event_id)) => {
+                if let Some(reply) = self.queued.pop_front() {
+                    self.pending.insert(event_id, reply);
+                }
+            }
+            res => {
+                if let Some(reply) = self.queued.pop_front() {
+                    let _ = reply.send(res);
+                }
+            }
+        }
+    }
+
+    fn close(&mut self, err: &io::Error) {
+        self.closed = true;
+        for reply in self
+            .queued
+            .drain(..)
+            .chain(self.pending.drain().map(|(_, r)| r))
+        {
+            let err = io::Error::new(err.kind(), err.to_string());
+            let _ = reply.send(Err(Error::read_stream(err)));
+        }
+    }
+}
+
+/// Sonic stream which can have many commands in flight at the same time.
+///
+/// Responses are read by a background thread and routed to the waiting caller by
+/// the event id, so the stream can be shared between threads without blocking
+/// each other until the server replies.
+///
+/// If the read timeout is set, the caller stops waiting for the reply after the
+/// timeout, but the connection stays usable, because the late reply is routed
+/// to nobody.
+#[derive(Debug)]
+pub struct MultiplexedStream {
+    stream: Mutex<Box<dyn Transport>>,
+    dispatcher: Arc<Mutex<Dispatcher>>,
+    protocol: Protocol,
+    server_info: ServerInfo,
+    read_timeout: Option<Duration>,
+}
+
+impl MultiplexedStream {
+    pub(crate) fn new(stream: SonicStream, read_timeout: Option<Duration>) -> Result<Self> {
+        let server_info = stream.server_info();
+        let (stream, mut reader, protocol) = stream.into_parts()?;
+        let dispatcher = Arc::new(Mutex::new(Dispatcher::default()));
+
+        let reader_dispatcher = dispatcher.clone();
+        thread::Builder::new()
+            .name(String::from("sonic-channel-reader"))
+            .spawn(move || {
+                // The dispatcher is consistent between the calls, so it's safe
+                // to continue after a panic in another thread.
+                let lock = || {
+                    reader_dispatcher
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                };
+                // The bytes are kept if the read timed out in the middle of
+                // the line.
+                let mut buf = Vec::new();
+                loop {
+                    let err = match reader.read_until(b'\n', &mut buf) {
+                        Ok(0) => Some(closed_by_server()),
+                        // The socket read timeout only limits the callers.
+                        Err(err)
+                            if matches!(
+                                err.kind(),
+                                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                            ) =>
+                        {
+                            continue
+                        }
+                        Err(err) => Some(err),
+                        Ok(_) => None,
+                    };
+                    if let Some(err) = err {
+                        lock().close(&err);
+                        break;
+                    }
+
+                    let line = String::from_utf8_lossy(&buf);
+                    log::debug!("[channel] {}", &line);
+                    let res = protocol.parse_response(&line);
+                    lock().dispatch(res);
+                    buf.clear();
+                }
+            })
+            .map_err(Error::ConnectToServer)?;
+
+        Ok(Self {
+            stream: Mutex::new(stream),
+            dispatcher,
+            protocol,
+            server_info,
+            read_timeout,
+        })
+    }
+
+    /// Returns information about the server of the connection.
+    pub fn server_info(&self) -> ServerInfo {
+        self.server_info.clone()
+    }
+
+    fn send(&self, req: protocol::Request) -> Result<mpsc::Receiver<Result<protocol::Response>>> {
+        let buf = self
+            .protocol
+            .format_request(req)
+            .map_err(Error::write_to_stream)?;
+
+        // The stream lock is held until the command is written, so the order of
+        // the queued replies always matches the order of the commands.
+        let mut stream = self
+            .stream
+            .lock()
+            .map_err(|_| Error::write_to_stream(poisoned()))?;
+        let (reply, receiver) = mpsc::channel();
+        {
+            let mut dispatcher = self
+                .dispatcher
+                .lock()
+                .map_err(|_| Error::write_to_stream(poisoned()))?;
+            if dispatcher.closed {
+                return Err(Error::write_to_stream(closed_by_server()));
+            }
+            dispatcher.queued.push_back(reply);
+        }
+
+        if let Err(err) = stream.write_all(&buf).and_then(|_| stream.flush()) {
+            // The reader thread fails all waiting callers as soon as it notices
+            // the broken connection.
+            let _ = stream.shutdown();
+            return Err(Error::write_to_stream(err));
+        }
+
+        Ok(receiver)
+    }
+
+    pub(crate) fn run_command<SC: StreamCommand>(&self, command: SC) -> Result<SC::Response> {
+        let req = command.request();
+        req.validate()?;
+        let name = req.name();
+
+        self.send(req)
+            .and_then(|receiver| self.receive(&receiver))
+            .and_then(|res| command.receive(res))
+            .map_err(|err| err.in_command(name, ""))
+    }
+}
+
+impl MultiplexedStream {
+    /// Waits for the reply until the read timeout.
+    fn receive(
+        &self,
+        receiver: &mpsc::Receiver<Result<protocol::Response>>,
+    ) -> Result<protocol::Response> {
+        match self.read_timeout {
+            None => receiver
+                .recv()
+                .map_err(|_| Error::read_stream(closed_by_server()))?,
+            Some(timeout) => match receiver.recv_timeout(timeout) {
+                Ok(res) => res,
+                Err(mpsc::RecvTimeoutError::Timeout) => Err(Error::timeout(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "no reply within the read timeout",
+                ))),
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    Err(Error::read_stream(closed_by_server()))
+                }
+            },
+        }
+    }
+}
+
+fn poisoned() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "the stream lock is poisoned")
+}
+
+impl Drop for MultiplexedStream {
+    fn drop(&mut self) {
+        if let Ok(stream) = self.stream.lock() {
+            // Wakes up the reader thread, so it can finish.
+            let _ = stream.shutdown();
+        }
+    }
+}
+
+/// The Sonic Channel Search mode which can run many commands concurrently over
+/// a single connection.
+///
+/// Unlike the [`SearchChannel`](crate::SearchChannel), this channel doesn't wait for
+/// the `EVENT` of the previous command before sending the next one. It can be shared
+/// between threads (e.g. in an `Arc`), and every caller receives the result of its
+/// own command.
+///
+/// Use [`ChannelBuilder::start_multiplexed`](crate::ChannelBuilder::start_multiplexed)
+/// to start the channel with the connection options. The channel doesn't
+/// reconnect: all waiting commands fail when the connection is broken, so a new
+/// channel should be started.
+///
+/// ### Available commands
+///
+/// In this mode you can use `query`, `suggest`, `list`, `help`, `ping` and `quit` commands.
+///
+/// **Note:** This mode requires enabling the `search` feature.
+#[derive(Debug)]
+pub struct MultiplexedSearchChannel(MultiplexedStream);
+
+impl MultiplexedSearchChannel {
+    /// Connects to sonic backend and run start command in Search mode.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # fn main() -> result::Result<()> {
+    /// let search_channel = MultiplexedSearchChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn start<A, S>(addr: A, password: S) -> Result<Self>
+    where
+        A: ToSocketAddrs,
+        S: ToString,
+    {
+        SonicStream::connect_with_start(ChannelMode::Search, addr, password)
+            .and_then(|stream| Self::from_stream(stream, None))
+    }
+
+    pub(crate) fn from_stream(stream: SonicStream, read_timeout: Option<Duration>) -> Result<Self> {
+        MultiplexedStream::new(stream, read_timeout).map(Self)
+    }
+
+    /// Returns reference for multiplexed stream of connection
+    pub fn stream(&self) -> &MultiplexedStream {
+        &self.0
+    }
+
+    /// Returns information about the sonic server. See [`ServerInfo`] for more
+    /// information.
+    pub fn server_info(&self) -> ServerInfo {
+        self.0.server_info()
+    }
+
+    /// Pings the server and returns the round-trip latency with information
+    /// about the server. See [`HealthCheck`] for more information.
+    pub fn health_check(&self) -> Result<HealthCheck> {
+        let started = Instant::now();
+        self.0.run_command(PingCommand)?;
+        Ok(HealthCheck::new(started.elapsed(), self.server_info()))
+    }
+}
+
+impl MultiplexedSearchChannel {
+    init_command!(
+        /// Stop connection.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # fn main() -> result::Result<()> {
+        /// let channel = MultiplexedSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// )?;
+        ///
+        /// channel.quit()?;
+        /// # Ok(())
+        /// # }
+        use QuitCommand for fn quit();
+    );
+
+    init_command!(
+        /// Ping server.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # fn main() -> result::Result<()> {
+        /// let channel = MultiplexedSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// )?;
+        ///
+        /// channel.ping()?;
+        /// # Ok(())
+        /// # }
+        use PingCommand for fn ping();
+    );
+
+    init_command!(
+        /// Show the manual of the server, e.g. the list of available commands.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # fn main() -> result::Result<()> {
+        /// let channel = MultiplexedSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// )?;
+        ///
+        /// let manuals = channel.help(HelpRequest::Manuals)?;
+        /// dbg!(manuals.entries);
+        /// # Ok(())
+        /// # }
+        use HelpCommand<'_> for fn help(
+            req: HelpRequest<'_>,
+        )
+    );
+
+    /// Returns the commands available in the channel mode.
+    ///
+    /// It can be used to detect the capabilities of the server.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # fn main() -> result::Result<()> {
+    /// let channel = MultiplexedSearchChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// )?;
+    ///
+    /// let commands = channel.commands()?;
+    /// assert!(commands.contains(&String::from("PING")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn commands(&self) -> Result<Vec<String>> {
+        self.help(HelpRequest::commands()).map(|res| res.entries)
+    }
+
+    init_command!(
+        /// Query objects in database.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # use std::sync::Arc;
+        /// # fn main() -> result::Result<()> {
+        /// let search_channel = Arc::new(MultiplexedSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// )?);
+        ///
+        /// let handles: Vec<_> = ["Beef", "Skewers"]
+        ///     .into_iter()
+        ///     .map(|terms| {
+        ///         let channel = search_channel.clone();
+        ///         std::thread::spawn(move || {
+        ///             channel.query(QueryRequest::new(Dest::col("search"), terms))
+        ///         })
+        ///     })
+        ///     .collect();
+        ///
+        /// for handle in handles {
+        ///     dbg!(handle.join().unwrap()?);
+        /// }
+        /// # Ok(())
+        /// # }
+        /// ```
+        use QueryCommand for fn query(
+            req: QueryRequest,
+        );
+    );
+
+    init_command!(
+        /// Suggest auto-completes words.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # fn main() -> result::Result<()> {
+        /// let search_channel = MultiplexedSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// )?;
+        ///
+        /// let result = search_channel.suggest(
+        ///     SuggestRequest::new(Dest::col("search"), "Beef")
+        /// )?;
+        /// dbg!(result);
+        /// # Ok(())
+        /// # }
+        /// ```
+        use SuggestCommand for fn suggest(
+            req: SuggestRequest,
+        );
+    );
+
+    init_command!(
+        /// Enumerates all words in an index.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # fn main() -> result::Result<()> {
+        /// let search_channel = MultiplexedSearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// )?;
+        ///
+        /// let result = search_channel.list(
+        ///     ListRequest::new(Dest::col("search"))
+        /// )?;
+        /// dbg!(result);
+        /// # Ok(())
+        /// # }
+        /// ```
+        use ListCommand for fn list(
+            req: ListRequest,
+        );
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: &str, objects: &[&str]) -> Result<protocol::Response> {
+        Ok(protocol::Response::Event(
+            protocol::EventKind::Query,
+            String::from(id),
+            objects.iter().map(|o| o.to_string()).collect(),
+        ))
+    }
+
+    fn objects(res: Result<protocol::Response>) -> Vec<String> {
+        match res {
+            Ok(protocol::Response::Event(_, _, objects)) => objects,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn should_route_events_by_id() {
+        let mut dispatcher = Dispatcher::default();
+        let (first, first_rx) = mpsc::channel();
+        let (second, second_rx) = mpsc::channel();
+        dispatcher.queued.push_back(first);
+        dispatcher.queued.push_back(second);
+
+        dispatcher.dispatch(Ok(protocol::Response::Pending(String::from("a"))));
+        dispatcher.dispatch(Ok(protocol::Response::Pending(String::from("b"))));
+        dispatcher.dispatch(event("b", &["2"]));
+        dispatcher.dispatch(event("a", &["1"]));
+
+        assert_eq!(objects(first_rx.recv().unwrap()), vec!["1"]);
+        assert_eq!(objects(second_rx.recv().unwrap()), vec!["2"]);
+        assert!(dispatcher.queued.is_empty() && dispatcher.pending.is_empty());
+    }
+
+    #[test]
+    fn should_reply_immediately_to_not_pending_commands() {
+        let mut dispatcher = Dispatcher::default();
+        let (query, query_rx) = mpsc::channel();
+        let (ping, ping_rx) = mpsc::channel();
+        dispatcher.queued.push_back(query);
+        dispatcher.queued.push_back(ping);
+
+        dispatcher.dispatch(Ok(protocol::Response::Pending(String::from("a"))));
+        dispatcher.dispatch(Ok(protocol::Response::Pong));
+
+        assert!(matches!(ping_rx.recv(), Ok(Ok(protocol::Response::Pong))));
+        assert!(query_rx.try_recv().is_err());
+
+        dispatcher.close(&closed_by_server());
+        assert!(matches!(query_rx.recv(), Ok(Err(Error::ReadStream { .. }))));
+        assert!(dispatcher.closed);
+    }
+}
@@ -0,0 +1,146 @@
+use super::ObjectIdCodec;
+use crate::result::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Defines what the sonic stream does when the connection is broken or was closed
+/// by the server (e.g. after the `tcp_timeout` of inactivity).
+///
+/// By default, the stream reconnects, runs the start command again and retries
+/// the command once. Any command is retried if the server never received it
+/// (the write failed or the server replied `ENDED`). If the connection broke
+/// after the command was sent, only idempotent commands (`query`, `suggest`,
+/// `list`, `count`, `ping`) are retried. Other commands return an error, but
+/// the next command will be sent over the new connection.
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # use std::time::Duration;
+/// # fn main() -> result::Result<()> {
+/// let search_channel: SearchChannel = ChannelBuilder::new("localhost:1491", "SecretPassword")
+///     .reconnect(ReconnectPolicy::default().max_attempts(5).backoff(Duration::from_secs(1)))
+///     .start()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Reconnect and run the start command again if the connection is broken.
+    pub enabled: bool,
+    /// Retry idempotent commands once after reconnect, even if the connection
+    /// broke after the command was sent.
+    pub retry_idempotent: bool,
+    /// The number of connection attempts before giving up.
+    pub max_attempts: usize,
+    /// Delay between connection attempts.
+    pub backoff: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retry_idempotent: true,
+            max_attempts: 3,
+            backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Creates a policy which never reconnects. All commands will fail after
+    /// the connection is broken.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+
+    /// Enable or disable the retry of idempotent commands after reconnect.
+    pub fn retry_idempotent(mut self, retry: bool) -> Self {
+        self.retry_idempotent = retry;
+        self
+    }
+
+    /// Set the number of connection attempts.
+    pub fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay between connection attempts.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+}
+
+/// Callback which receives the errors of the keepalive `PING` command.
+pub type KeepaliveErrorHandler = Arc<dyn Fn(&Error) + Send + Sync>;
+
+/// Sends `PING` on the idle connection, so sonic doesn't close it after the
+/// `tcp_timeout` of inactivity.
+///
+/// The `PING` command is sent from the background thread only if no other command
+/// was sent during the interval. A connection which is running a command counts
+/// as active, so the `PING` is skipped until the next check. The thread stops
+/// when the channel is dropped.
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # use std::time::Duration;
+/// # fn main() -> result::Result<()> {
+/// let search_channel: SearchChannel = ChannelBuilder::new("localhost:1491", "SecretPassword")
+///     .keepalive(Keepalive::new(Duration::from_secs(60)).on_error(|err| {
+///         eprintln!("keepalive failed: {}", err);
+///     }))
+///     .start()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Keepalive {
+    pub(crate) interval: Duration,
+    pub(crate) on_error: Option<KeepaliveErrorHandler>,
+}
+
+impl std::fmt::Debug for Keepalive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Keepalive")
+            .field("interval", &self.interval)
+            .field("on_error", &self.on_error.is_some())
+            .finish()
+    }
+}
+
+impl Keepalive {
+    /// Creates keepalive which pings the connection after the interval of inactivity.
+    ///
+    /// The interval must be greater than zero, otherwise the channel fails to
+    /// start with the [`Error::InvalidOptions`] error.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            on_error: None,
+        }
+    }
+
+    /// Set the callback which is called if the `PING` command failed, even after
+    /// reconnect.
+    pub fn on_error(mut self, handler: impl Fn(&Error) + Send + Sync + 'static) -> Self {
+        self.on_error = Some(Arc::new(handler));
+        self
+    }
+}
+
+/// Options of the sonic stream which are set by the [`ChannelBuilder`](super::ChannelBuilder).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StreamOptions {
+    pub(crate) reconnect: ReconnectPolicy,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) read_timeout: Option<Duration>,
+    pub(crate) write_timeout: Option<Duration>,
+    pub(crate) keepalive: Option<Keepalive>,
+    pub(crate) object_id_codec: Option<Arc<dyn ObjectIdCodec>>,
+}
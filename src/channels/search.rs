@@ -1,7 +1,6 @@
 use super::{ChannelMode, SonicChannel, SonicStream};
 use crate::commands::*;
 use crate::result::Result;
-use std::net::ToSocketAddrs;
 
 /// The Sonic Channel Search mode is used for querying the search index.
 /// Once in this mode, you cannot switch to other modes or gain access
@@ -9,7 +8,7 @@ use std::net::ToSocketAddrs;
 ///
 /// ### Available commands
 ///
-/// In this mode you can use `query`, `suggest`, `ping` and `quit` commands.
+/// In this mode you can use `query`, `suggest`, `help`, `ping` and `quit` commands.
 ///
 /// **Note:** This mode requires enabling the `search` feature.
 #[derive(Debug)]
@@ -18,16 +17,14 @@ pub struct SearchChannel(SonicStream);
 impl SonicChannel for SearchChannel {
     type Channel = SearchChannel;
 
+    const MODE: ChannelMode = ChannelMode::Search;
+
     fn stream(&self) -> &SonicStream {
         &self.0
     }
 
-    fn start<A, S>(addr: A, password: S) -> Result<Self::Channel>
-    where
-        A: ToSocketAddrs,
-        S: ToString,
-    {
-        SonicStream::connect_with_start(ChannelMode::Search, addr, password).map(Self)
+    fn from_stream(stream: SonicStream) -> Self::Channel {
+        Self(stream)
     }
 }
 
@@ -65,6 +62,47 @@ impl SearchChannel {
         /// # }
         use PingCommand for fn ping();
     );
+
+    init_command!(
+        /// Show the manual of the server, e.g. the list of available commands.
+        ///
+        /// ```rust,no_run
+        /// # use sonic_channel::*;
+        /// # fn main() -> result::Result<()> {
+        /// let channel = SearchChannel::start(
+        ///     "localhost:1491",
+        ///     "SecretPassword",
+        /// )?;
+        ///
+        /// let manuals = channel.help(HelpRequest::Manuals)?;
+        /// dbg!(manuals.entries);
+        /// # Ok(())
+        /// # }
+        use HelpCommand<'_> for fn help(
+            req: HelpRequest<'_>,
+        )
+    );
+
+    /// Returns the commands available in the channel mode.
+    ///
+    /// It can be used to detect the capabilities of the server.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # fn main() -> result::Result<()> {
+    /// let channel = SearchChannel::start(
+    ///     "localhost:1491",
+    ///     "SecretPassword",
+    /// )?;
+    ///
+    /// let commands = channel.commands()?;
+    /// assert!(commands.contains(&String::from("PING")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn commands(&self) -> Result<Vec<String>> {
+        self.help(HelpRequest::commands()).map(|res| res.entries)
+    }
 }
 
 impl SearchChannel {
@@ -0,0 +1,172 @@
+use super::ChannelMode;
+use crate::result::*;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Version of the sonic server from the `CONNECTED` banner.
+///
+/// Versions are ordered, so they can be used to enable features of newer servers.
+///
+/// ```rust
+/// # use sonic_channel::*;
+/// let version: ServerVersion = "1.4.0".parse().unwrap();
+/// assert!(version >= ServerVersion::new(1, 3, 0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ServerVersion {
+    /// Major version.
+    pub major: u32,
+    /// Minor version.
+    pub minor: u32,
+    /// Patch version.
+    pub patch: u32,
+}
+
+impl ServerVersion {
+    /// Creates a new version.
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl std::fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for ServerVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.strip_prefix('v').unwrap_or(s);
+        let mut parts = s.splitn(3, '.').map(|part| {
+            // Ignores pre-release and build metadata, e.g. `1.4.0-beta`.
+            let digits = part
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(part, |end| &part[..end]);
+            digits.parse::<u32>().map_err(|_| Error::wrong_response(s))
+        });
+
+        let major = parts.next().ok_or_else(|| Error::wrong_response(s))??;
+        let minor = parts.next().transpose()?.unwrap_or(0);
+        let patch = parts.next().transpose()?.unwrap_or(0);
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+/// Information about the sonic server of the started channel.
+///
+/// It's updated after reconnect, because the channel may connect to another
+/// server.
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # fn main() -> result::Result<()> {
+/// let search_channel = SearchChannel::start("localhost:1491", "SecretPassword")?;
+/// let info = search_channel.server_info();
+/// println!(
+///     "connected to sonic {:?} (protocol {}, buffer {})",
+///     info.version, info.protocol_version, info.max_buffer_size
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerInfo {
+    /// The banner of the `CONNECTED` response, e.g. `<sonic-server v1.4.0>`.
+    pub banner: String,
+    /// Server version parsed from the banner, if the banner has a known format.
+    pub version: Option<ServerVersion>,
+    /// Version of the protocol negotiated by the start command.
+    pub protocol_version: u8,
+    /// Maximum size of the command line accepted by the server.
+    pub max_buffer_size: usize,
+    /// The mode of the channel.
+    pub mode: ChannelMode,
+}
+
+/// Result of the health check of the channel.
+///
+/// The health check pings the server, so it can be used for readiness probes.
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # fn main() -> result::Result<()> {
+/// let search_channel = SearchChannel::start("localhost:1491", "SecretPassword")?;
+/// let health = search_channel.health_check()?;
+/// println!(
+///     "sonic {:?} replied in {:?} (mode {}, buffer {})",
+///     health.version, health.latency, health.mode, health.max_buffer_size
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthCheck {
+    /// Round-trip time of the `PING` command.
+    pub latency: Duration,
+    /// The mode of the channel.
+    pub mode: ChannelMode,
+    /// Maximum size of the command line accepted by the server.
+    pub max_buffer_size: usize,
+    /// Server version parsed from the banner, if the banner has a known format.
+    pub version: Option<ServerVersion>,
+}
+
+impl HealthCheck {
+    pub(crate) fn new(latency: Duration, info: ServerInfo) -> Self {
+        Self {
+            latency,
+            mode: info.mode,
+            max_buffer_size: info.max_buffer_size,
+            version: info.version,
+        }
+    }
+}
+
+/// Parses the server version from the banner, e.g. `<sonic-server v1.4.0>`.
+pub(crate) fn parse_banner_version(banner: &str) -> Option<ServerVersion> {
+    banner
+        .trim_matches(|c| c == '<' || c == '>')
+        .split_whitespace()
+        .filter(|part| part.starts_with('v'))
+        .find_map(|part| part.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_server_version() {
+        assert_eq!(
+            "1.4.0".parse::<ServerVersion>().ok(),
+            Some(ServerVersion::new(1, 4, 0))
+        );
+        assert_eq!(
+            "v1.3".parse::<ServerVersion>().ok(),
+            Some(ServerVersion::new(1, 3, 0))
+        );
+        assert_eq!(
+            "1.4.1-beta".parse::<ServerVersion>().ok(),
+            Some(ServerVersion::new(1, 4, 1))
+        );
+        assert!("sonic".parse::<ServerVersion>().is_err());
+        assert!(ServerVersion::new(1, 4, 0) > ServerVersion::new(1, 3, 9));
+    }
+
+    #[test]
+    fn should_parse_version_from_banner() {
+        assert_eq!(
+            parse_banner_version("<sonic-server v1.4.0>"),
+            Some(ServerVersion::new(1, 4, 0))
+        );
+        assert_eq!(parse_banner_version("<sonic-server>"), None);
+        assert_eq!(parse_banner_version(""), None);
+    }
+}
@@ -0,0 +1,139 @@
+use std::io;
+use std::net::TcpStream;
+use std::sync::Arc;
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName};
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+
+use crate::result::*;
+
+pub(crate) type TlsStream = StreamOwned<ClientConnection, TcpStream>;
+
+/// TLS settings of the connection to sonic server.
+///
+/// Sonic doesn't support TLS by itself, so it's usually started behind a TLS
+/// terminating proxy. The server certificate is verified against the added root
+/// certificates, and the host of the channel address is sent as SNI unless
+/// another server name is set.
+///
+/// **Note:** This requires enabling the `tls` feature.
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # fn main() -> result::Result<()> {
+/// let tls = TlsConfig::new()
+///     .add_root_certificates_pem(&std::fs::read("ca.pem").unwrap())?
+///     .server_name("sonic.example.com");
+///
+/// let search_channel: SearchChannel = ChannelBuilder::new("10.0.0.5:1491", "SecretPassword")
+///     .tls(tls)
+///     .start()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    roots: RootCertStore,
+    server_name: Option<String>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TlsConfig {
+    /// Creates a config without trusted root certificates.
+    pub fn new() -> Self {
+        Self {
+            roots: RootCertStore::empty(),
+            server_name: None,
+        }
+    }
+
+    /// Trust the root certificate in DER format.
+    pub fn add_root_certificate_der(mut self, der: &[u8]) -> Result<Self> {
+        self.roots
+            .add(CertificateDer::from(der.to_vec()))
+            .map_err(|e| Error::Tls(e.into()))?;
+        Ok(self)
+    }
+
+    /// Trust all root certificates from the PEM file content.
+    pub fn add_root_certificates_pem(mut self, pem: &[u8]) -> Result<Self> {
+        let mut found = false;
+        for cert in CertificateDer::pem_slice_iter(pem) {
+            let cert = cert.map_err(|e| Error::Tls(e.into()))?;
+            self.roots.add(cert).map_err(|e| Error::Tls(e.into()))?;
+            found = true;
+        }
+
+        if found {
+            Ok(self)
+        } else {
+            Err(Error::Tls("no certificates found in PEM".into()))
+        }
+    }
+
+    /// Set the server name which is sent as SNI and verified against the server
+    /// certificate. By default, the host of the channel address is used.
+    pub fn server_name(mut self, name: impl ToString) -> Self {
+        self.server_name = Some(name.to_string());
+        self
+    }
+
+    pub(crate) fn connector(&self, default_host: &str) -> Result<TlsConnector> {
+        if self.roots.is_empty() {
+            return Err(Error::Tls("no root certificates".into()));
+        }
+
+        let name = self.server_name.as_deref().unwrap_or(default_host);
+        let server_name =
+            ServerName::try_from(name.to_string()).map_err(|e| Error::Tls(e.into()))?;
+
+        // The provider is chosen explicitly, so the process default provider
+        // isn't required.
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| Error::Tls(e.into()))?
+            .with_root_certificates(self.roots.clone())
+            .with_no_client_auth();
+
+        Ok(TlsConnector {
+            config: Arc::new(config),
+            server_name,
+        })
+    }
+}
+
+/// Prepared TLS client config which is shared by all connections of the stream.
+#[derive(Debug, Clone)]
+pub(crate) struct TlsConnector {
+    config: Arc<ClientConfig>,
+    server_name: ServerName<'static>,
+}
+
+impl TlsConnector {
+    pub(crate) fn connect(&self, stream: TcpStream) -> Result<TlsStream> {
+        let conn = ClientConnection::new(self.config.clone(), self.server_name.clone())
+            .map_err(|e| Error::Tls(e.into()))?;
+        let mut stream = StreamOwned::new(conn, stream);
+
+        // Complete the handshake right away, so the certificate errors are
+        // reported as connection errors instead of the broken stream.
+        while stream.conn.is_handshaking() {
+            stream
+                .conn
+                .complete_io(&mut stream.sock)
+                .map_err(|e| match e.kind() {
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Error::timeout(e),
+                    _ => Error::Tls(e.into()),
+                })?;
+        }
+
+        Ok(stream)
+    }
+}
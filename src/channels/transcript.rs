@@ -0,0 +1,295 @@
+use super::{Connector, Transport};
+use crate::result::*;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Replaces the password of the recorded `START` command.
+pub const REDACTED_PASSWORD: &str = "********";
+
+/// A line of the transcript without the line ending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptLine {
+    /// The line sent by the client, written as `> <line>`.
+    Sent(String),
+    /// The line received from the server, written as `< <line>`.
+    Received(String),
+}
+
+impl std::fmt::Display for TranscriptLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranscriptLine::Sent(line) => write!(f, "> {}", line),
+            TranscriptLine::Received(line) => write!(f, "< {}", line),
+        }
+    }
+}
+
+/// Lines exchanged with the sonic server in the order they were sent and
+/// received.
+///
+/// Every connection starts with the `CONNECTED` greeting of the server, so the
+/// transcript may contain several connections, e.g. after reconnect.
+///
+/// ```rust
+/// # use sonic_channel::*;
+/// let transcript: Transcript = "\
+/// < CONNECTED <sonic-server v1.4.9>
+/// > START search ********
+/// < STARTED search protocol(1) buffer_size(20000)
+/// > PING
+/// < PONG
+/// ".parse().unwrap();
+///
+/// assert_eq!(transcript.lines().len(), 5);
+/// assert_eq!(transcript.lines()[3], TranscriptLine::Sent(String::from("PING")));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transcript {
+    lines: Vec<TranscriptLine>,
+}
+
+impl Transcript {
+    /// Creates the transcript from the lines.
+    pub fn new(lines: Vec<TranscriptLine>) -> Self {
+        Self { lines }
+    }
+
+    /// Reads the transcript file written by the [`TranscriptRecorder`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Returns the lines of the transcript.
+    pub fn lines(&self) -> &[TranscriptLine] {
+        &self.lines
+    }
+}
+
+impl FromStr for Transcript {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        s.lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                if let Some(sent) = line.strip_prefix("> ") {
+                    Ok(TranscriptLine::Sent(sent.to_string()))
+                } else if let Some(received) = line.strip_prefix("< ") {
+                    Ok(TranscriptLine::Received(received.to_string()))
+                } else {
+                    Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid transcript line `{}`", line.escape_debug()),
+                    ))
+                }
+            })
+            .collect::<io::Result<_>>()
+            .map(Self::new)
+    }
+}
+
+impl std::fmt::Display for Transcript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the lines exchanged with the sonic server to the transcript, which
+/// can be replayed with the `testing::ReplayServer`.
+///
+/// The password of the `START` command is replaced with the
+/// [`REDACTED_PASSWORD`].
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # fn main() -> result::Result<()> {
+/// let recorder = TranscriptRecorder::create("sonic.transcript").unwrap();
+/// let search_channel: SearchChannel = ChannelBuilder::new("localhost:1491", "SecretPassword")
+///     .record(recorder)
+///     .start()?;
+///
+/// search_channel.query(QueryRequest::new(Dest::col("search"), "Beef"))?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TranscriptRecorder {
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+impl std::fmt::Debug for TranscriptRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TranscriptRecorder").finish_non_exhaustive()
+    }
+}
+
+impl TranscriptRecorder {
+    /// Creates the recorder which writes the transcript to the writer.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            sink: Mutex::new(Box::new(writer)),
+        }
+    }
+
+    /// Creates the recorder which writes the transcript to the new file.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        File::create(path).map(|file| Self::new(BufWriter::new(file)))
+    }
+
+    fn record(&self, line: TranscriptLine) {
+        let mut sink = self.sink.lock().unwrap_or_else(PoisonError::into_inner);
+        // The recording must not break the channel, so the errors are only logged.
+        if let Err(err) = writeln!(sink, "{}", line).and_then(|_| sink.flush()) {
+            log::warn!("[channel] cannot record transcript: {}", err);
+        }
+    }
+}
+
+/// Opens the transports which record the exchanged lines.
+pub(crate) struct RecordingConnector {
+    pub(crate) connector: Arc<dyn Connector>,
+    pub(crate) recorder: Arc<TranscriptRecorder>,
+}
+
+impl Connector for RecordingConnector {
+    fn connect(&self) -> Result<Box<dyn Transport>> {
+        let transport = self.connector.connect()?;
+        Ok(Box::new(RecordingTransport {
+            transport,
+            recorder: self.recorder.clone(),
+            sent: Vec::new(),
+            received: Vec::new(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct RecordingTransport {
+    transport: Box<dyn Transport>,
+    recorder: Arc<TranscriptRecorder>,
+    /// Bytes of the incomplete lines.
+    sent: Vec<u8>,
+    received: Vec<u8>,
+}
+
+impl RecordingTransport {
+    /// Records the complete lines of the buffer.
+    fn record_lines(
+        recorder: &TranscriptRecorder,
+        buf: &mut Vec<u8>,
+        line: fn(String) -> TranscriptLine,
+    ) {
+        while let Some(end) = buf.iter().position(|&b| b == b'\n') {
+            let raw = buf.drain(..=end).collect::<Vec<_>>();
+            let raw = String::from_utf8_lossy(&raw);
+            recorder.record(line(redact(raw.trim_end_matches(['\r', '\n']))));
+        }
+    }
+}
+
+/// Replaces the password of the `START` command.
+pub(crate) fn redact(line: &str) -> String {
+    let mut parts = line.splitn(3, ' ');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some("START"), Some(mode), Some(_)) => format!("START {} {}", mode, REDACTED_PASSWORD),
+        _ => line.to_string(),
+    }
+}
+
+impl Read for RecordingTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.transport.read(buf)?;
+        self.received.extend_from_slice(&buf[..n]);
+        Self::record_lines(&self.recorder, &mut self.received, TranscriptLine::Received);
+        Ok(n)
+    }
+}
+
+impl Write for RecordingTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.transport.write(buf)?;
+        self.sent.extend_from_slice(&buf[..n]);
+        Self::record_lines(&self.recorder, &mut self.sent, TranscriptLine::Sent);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.transport.flush()
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn shutdown(&self) -> io::Result<()> {
+        self.transport.shutdown()
+    }
+
+    fn try_clone(&self) -> io::Result<Box<dyn Transport>> {
+        Ok(Box::new(RecordingTransport {
+            transport: self.transport.try_clone()?,
+            recorder: self.recorder.clone(),
+            sent: Vec::new(),
+            received: Vec::new(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writer which shares the written bytes with the test.
+    #[derive(Debug, Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn should_parse_and_format_transcript() {
+        let raw = "< CONNECTED <sonic-server v1.4.9>\n> PING\n< PONG\n";
+        let transcript: Transcript = raw.parse().unwrap();
+        assert_eq!(
+            transcript.lines(),
+            &[
+                TranscriptLine::Received(String::from("CONNECTED <sonic-server v1.4.9>")),
+                TranscriptLine::Sent(String::from("PING")),
+                TranscriptLine::Received(String::from("PONG")),
+            ]
+        );
+        assert_eq!(transcript.to_string(), raw);
+
+        assert!("PING".parse::<Transcript>().is_err());
+    }
+
+    #[test]
+    fn should_record_complete_lines() {
+        let buf = SharedBuf::default();
+        let recorder = TranscriptRecorder::new(buf.clone());
+
+        let mut pending = b"START search secret pass\r\nPI".to_vec();
+        RecordingTransport::record_lines(&recorder, &mut pending, TranscriptLine::Sent);
+        assert_eq!(pending, b"PI");
+
+        let mut received = b"PENDING a\r\nEVENT QUERY a obj:1\r\n".to_vec();
+        RecordingTransport::record_lines(&recorder, &mut received, TranscriptLine::Received);
+        assert!(received.is_empty());
+
+        assert_eq!(
+            String::from_utf8(buf.0.lock().unwrap().clone()).unwrap(),
+            "> START search ********\n< PENDING a\n< EVENT QUERY a obj:1\n"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+use super::{io_error, StreamOptions};
+use crate::result::*;
+
+/// Bidirectional byte stream which carries the sonic protocol.
+///
+/// It's implemented for [`TcpStream`] and [`UnixStream`] (e.g. a local socat
+/// bridge to the sonic server), but any other stream can be used with a custom
+/// [`Connector`], for example an in-memory duplex in unit tests.
+pub trait Transport: Read + Write + Send + std::fmt::Debug {
+    /// Closes both directions of the transport, so the blocked reads and writes
+    /// fail immediately.
+    fn shutdown(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Creates an independent handle to the same transport, which is used for
+    /// writing while another thread is reading.
+    ///
+    /// Transports which cannot be split return the `Unsupported` error, so they
+    /// can't be used by the multiplexed channels.
+    fn try_clone(&self) -> io::Result<Box<dyn Transport>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "transport cannot be split",
+        ))
+    }
+}
+
+impl Transport for TcpStream {
+    fn shutdown(&self) -> io::Result<()> {
+        TcpStream::shutdown(self, Shutdown::Both)
+    }
+
+    fn try_clone(&self) -> io::Result<Box<dyn Transport>> {
+        TcpStream::try_clone(self).map(|s| Box::new(s) as Box<dyn Transport>)
+    }
+}
+
+#[cfg(unix)]
+impl Transport for UnixStream {
+    fn shutdown(&self) -> io::Result<()> {
+        UnixStream::shutdown(self, Shutdown::Both)
+    }
+
+    fn try_clone(&self) -> io::Result<Box<dyn Transport>> {
+        UnixStream::try_clone(self).map(|s| Box::new(s) as Box<dyn Transport>)
+    }
+}
+
+#[cfg(feature = "tls")]
+impl Transport for super::TlsStream {
+    fn shutdown(&self) -> io::Result<()> {
+        self.sock.shutdown(Shutdown::Both)
+    }
+}
+
+/// Opens new transports to the sonic server.
+///
+/// The sonic stream uses the connector to open the first connection and to
+/// reconnect after the connection was broken. It's implemented for closures,
+/// so a custom transport can be passed to the
+/// [`ChannelBuilder::with_connector`](super::ChannelBuilder::with_connector).
+pub trait Connector: Send + Sync {
+    /// Opens a new transport.
+    fn connect(&self) -> Result<Box<dyn Transport>>;
+}
+
+impl<F, T> Connector for F
+where
+    F: Fn() -> io::Result<T> + Send + Sync,
+    T: Transport + 'static,
+{
+    fn connect(&self) -> Result<Box<dyn Transport>> {
+        self()
+            .map(|t| Box::new(t) as Box<dyn Transport>)
+            .map_err(|e| io_error(e, Error::ConnectToServer))
+    }
+}
+
+/// Resolved socket addresses of the sonic server.
+#[derive(Debug)]
+pub(crate) struct HostAddrs {
+    pub(crate) addrs: Vec<SocketAddr>,
+    #[cfg(feature = "tls")]
+    pub(crate) tls: Option<super::TlsConnector>,
+}
+
+impl HostAddrs {
+    pub(crate) fn new(addrs: Vec<SocketAddr>) -> Self {
+        Self {
+            addrs,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+}
+
+/// Connects to the first available host with the stream options.
+#[derive(Debug)]
+pub(crate) struct TcpConnector {
+    pub(crate) hosts: Vec<HostAddrs>,
+    pub(crate) options: StreamOptions,
+}
+
+impl TcpConnector {
+    fn connect_host(&self, host: &HostAddrs) -> Result<Box<dyn Transport>> {
+        let options = &self.options;
+        let stream = match options.connect_timeout {
+            None => TcpStream::connect(&host.addrs[..])
+                .map_err(|e| io_error(e, Error::ConnectToServer))?,
+            Some(timeout) => connect_timeout(&host.addrs, timeout)?,
+        };
+        stream
+            .set_read_timeout(options.read_timeout)
+            .and_then(|_| stream.set_write_timeout(options.write_timeout))
+            .map_err(Error::ConnectToServer)?;
+
+        #[cfg(feature = "tls")]
+        if let Some(connector) = &host.tls {
+            return Ok(Box::new(connector.connect(stream)?));
+        }
+
+        Ok(Box::new(stream))
+    }
+}
+
+impl Connector for TcpConnector {
+    fn connect(&self) -> Result<Box<dyn Transport>> {
+        let mut last_err = Error::ConnectToServer(no_addresses());
+        for host in &self.hosts {
+            match self.connect_host(host) {
+                Ok(transport) => return Ok(transport),
+                Err(err) => {
+                    log::warn!("[channel] cannot connect to {:?}: {}", host.addrs, err);
+                    last_err = err;
+                }
+            }
+        }
+        Err(last_err)
+    }
+}
+
+fn connect_timeout(addrs: &[SocketAddr], timeout: Duration) -> Result<TcpStream> {
+    let mut last_err = Error::ConnectToServer(no_addresses());
+    for addr in addrs {
+        match TcpStream::connect_timeout(addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = io_error(err, Error::ConnectToServer),
+        }
+    }
+    Err(last_err)
+}
+
+fn no_addresses() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "no addresses to connect")
+}
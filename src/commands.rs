@@ -1,3 +1,4 @@
+mod help;
 mod ping;
 mod quit;
 mod start;
@@ -10,6 +11,8 @@ mod flush;
 mod pop;
 #[cfg(feature = "ingest")]
 mod push;
+#[cfg(feature = "ingest")]
+mod split;
 
 #[cfg(feature = "search")]
 mod list;
@@ -18,11 +21,21 @@ mod query;
 #[cfg(feature = "search")]
 mod suggest;
 
+#[cfg(feature = "control")]
+mod info;
 #[cfg(feature = "control")]
 mod trigger;
 
-pub(crate) use self::{ping::PingCommand, quit::QuitCommand, start::StartCommand};
+pub use self::help::{HelpRequest, HelpResponse};
+pub(crate) use self::{
+    help::HelpCommand,
+    ping::PingCommand,
+    quit::QuitCommand,
+    start::{StartCommand, StartCommandResponse},
+};
 
+#[cfg(feature = "ingest")]
+pub(crate) use self::split::SplitCommand;
 #[cfg(feature = "ingest")]
 pub(crate) use self::{
     count::CountCommand, flush::FlushCommand, pop::PopCommand, push::PushCommand,
@@ -36,9 +49,9 @@ pub(crate) use self::{list::ListCommand, query::QueryCommand, suggest::SuggestCo
 pub use self::{list::ListRequest, query::QueryRequest, suggest::SuggestRequest};
 
 #[cfg(feature = "control")]
-pub(crate) use trigger::TriggerCommand;
+pub(crate) use self::{info::InfoCommand, trigger::TriggerCommand};
 #[cfg(feature = "control")]
-pub use trigger::TriggerRequest;
+pub use self::{info::ServerStats, trigger::TriggerRequest};
 
 use crate::protocol;
 use crate::result::Result;
@@ -50,4 +63,9 @@ pub trait StreamCommand {
     fn request(&self) -> protocol::Request;
 
     fn receive(&self, res: protocol::Response) -> Result<Self::Response>;
+
+    /// Returns true if the command can be safely sent again, e.g. after reconnect.
+    fn is_idempotent(&self) -> bool {
+        false
+    }
 }
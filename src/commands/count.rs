@@ -61,7 +61,11 @@ impl StreamCommand for CountCommand {
         if let protocol::Response::Result(count) = res {
             Ok(count)
         } else {
-            Err(Error::WrongResponse)
+            Err(Error::unexpected_response())
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
 }
@@ -62,7 +62,7 @@ impl StreamCommand for FlushCommand {
         if let protocol::Response::Result(count) = res {
             Ok(count)
         } else {
-            Err(Error::WrongResponse)
+            Err(Error::unexpected_response())
         }
     }
 }
@@ -0,0 +1,133 @@
+use super::StreamCommand;
+use crate::protocol;
+use crate::result::*;
+
+/// Parameters for the `help` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpRequest<'a> {
+    /// List the manuals of the server, e.g. `commands`.
+    Manuals,
+
+    /// Show the manual with the given name.
+    Manual(&'a str),
+}
+
+impl HelpRequest<'_> {
+    /// Show the commands available in the current channel mode.
+    pub fn commands() -> Self {
+        Self::Manual("commands")
+    }
+}
+
+/// The manual returned by the `help` command.
+///
+/// For example, the `commands` manual of the search channel contains `QUERY`,
+/// `SUGGEST`, `LIST`, `PING`, `HELP` and `QUIT` entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpResponse {
+    /// Name of the manual, e.g. `manuals` or `commands`.
+    pub manual: String,
+    /// Entries of the manual.
+    pub entries: Vec<String>,
+}
+
+impl HelpResponse {
+    /// Returns true if the manual contains the entry, e.g. the command name.
+    pub fn contains(&self, entry: &str) -> bool {
+        self.entries.iter().any(|e| e.eq_ignore_ascii_case(entry))
+    }
+}
+
+#[derive(Debug)]
+pub struct HelpCommand<'a> {
+    pub(crate) req: HelpRequest<'a>,
+}
+
+impl StreamCommand for HelpCommand<'_> {
+    type Response = HelpResponse;
+
+    fn request(&self) -> protocol::Request {
+        let manual = match self.req {
+            HelpRequest::Manuals => None,
+            HelpRequest::Manual(manual) => Some(manual.to_string()),
+        };
+
+        protocol::Request::Help(manual)
+    }
+
+    fn receive(&self, res: protocol::Response) -> Result<Self::Response> {
+        match res {
+            protocol::Response::Values(values) if values.len() == 1 => {
+                let (manual, entries) = values.into_iter().next().unwrap();
+                let entries = entries
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|entry| !entry.is_empty())
+                    .map(String::from)
+                    .collect();
+                Ok(HelpResponse { manual, entries })
+            }
+            _ => Err(Error::unexpected_response()),
+        }
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Protocol;
+
+    fn receive(req: HelpRequest, line: &str) -> Result<HelpResponse> {
+        let res = Protocol::default().parse_response(line)?;
+        HelpCommand { req }.receive(res)
+    }
+
+    #[test]
+    fn should_format_help_request() {
+        let protocol = Protocol::default();
+        let format = |req| {
+            let buf = protocol
+                .format_request(HelpCommand { req }.request())
+                .unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+
+        assert_eq!(format(HelpRequest::Manuals), "HELP\r\n");
+        assert_eq!(format(HelpRequest::commands()), "HELP commands\r\n");
+    }
+
+    #[test]
+    fn should_parse_manuals() {
+        let res = receive(HelpRequest::Manuals, "RESULT manuals(commands)\r\n").unwrap();
+        assert_eq!(res.manual, "manuals");
+        assert_eq!(res.entries, vec!["commands"]);
+    }
+
+    #[test]
+    fn should_parse_commands() {
+        let res = receive(
+            HelpRequest::commands(),
+            "RESULT commands(QUERY, SUGGEST, LIST, PING, HELP, QUIT)\r\n",
+        )
+        .unwrap();
+        assert_eq!(res.manual, "commands");
+        assert_eq!(
+            res.entries,
+            vec!["QUERY", "SUGGEST", "LIST", "PING", "HELP", "QUIT"]
+        );
+        assert!(res.contains("suggest"));
+        assert!(!res.contains("PUSH"));
+    }
+
+    #[test]
+    fn should_reject_unknown_manual() {
+        match receive(HelpRequest::Manual("unknown"), "ERR not_found\r\n") {
+            Err(Error::SonicServer(ServerError::NotFound)) => {}
+            _ => unreachable!(),
+        }
+    }
+}
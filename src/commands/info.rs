@@ -0,0 +1,133 @@
+use super::StreamCommand;
+use crate::protocol;
+use crate::result::*;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Statistics of the sonic server returned by the `info` command.
+///
+/// Values which aren't reported by the server are left with default values.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerStats {
+    /// Time since the server started.
+    pub uptime: Duration,
+    /// Number of the connected clients.
+    pub clients_connected: u32,
+    /// Number of the commands executed since the server started.
+    pub commands_total: u64,
+    /// Best execution time of the command.
+    pub command_latency_best: Duration,
+    /// Worst execution time of the command.
+    pub command_latency_worst: Duration,
+    /// Number of the open key-value stores.
+    pub kv_open_count: usize,
+    /// Number of the open FST graphs.
+    pub fst_open_count: usize,
+    /// Number of the FST graphs waiting for consolidation.
+    pub fst_consolidate_count: usize,
+}
+
+impl ServerStats {
+    fn from_values(values: Vec<(String, String)>) -> Result<Self> {
+        let mut stats = Self::default();
+        for (key, value) in values {
+            match key.as_str() {
+                "uptime" => stats.uptime = Duration::from_secs(parse_value(&value)?),
+                "clients_connected" => stats.clients_connected = parse_value(&value)?,
+                "commands_total" => stats.commands_total = parse_value(&value)?,
+                "command_latency_best" => {
+                    stats.command_latency_best = Duration::from_millis(parse_value(&value)?)
+                }
+                "command_latency_worst" => {
+                    stats.command_latency_worst = Duration::from_millis(parse_value(&value)?)
+                }
+                "kv_open_count" => stats.kv_open_count = parse_value(&value)?,
+                "fst_open_count" => stats.fst_open_count = parse_value(&value)?,
+                "fst_consolidate_count" => stats.fst_consolidate_count = parse_value(&value)?,
+                // Newer servers may report more statistics.
+                _ => {}
+            }
+        }
+        Ok(stats)
+    }
+}
+
+fn parse_value<T: FromStr>(value: &str) -> Result<T> {
+    value.parse().map_err(|_| Error::unexpected_response())
+}
+
+#[derive(Debug)]
+pub struct InfoCommand;
+
+impl StreamCommand for InfoCommand {
+    type Response = ServerStats;
+
+    fn request(&self) -> protocol::Request {
+        protocol::Request::Info
+    }
+
+    fn receive(&self, res: protocol::Response) -> Result<Self::Response> {
+        if let protocol::Response::Values(values) = res {
+            ServerStats::from_values(values)
+        } else {
+            Err(Error::unexpected_response())
+        }
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Protocol;
+
+    fn receive(line: &str) -> Result<ServerStats> {
+        let res = Protocol::default().parse_response(line)?;
+        InfoCommand.receive(res)
+    }
+
+    #[test]
+    fn should_parse_server_stats() {
+        let stats = receive(
+            "RESULT uptime(12) clients_connected(1) commands_total(3) command_latency_best(1) \
+             command_latency_worst(2) kv_open_count(1) fst_open_count(0) fst_consolidate_count(4)\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            stats,
+            ServerStats {
+                uptime: Duration::from_secs(12),
+                clients_connected: 1,
+                commands_total: 3,
+                command_latency_best: Duration::from_millis(1),
+                command_latency_worst: Duration::from_millis(2),
+                kv_open_count: 1,
+                fst_open_count: 0,
+                fst_consolidate_count: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn should_ignore_unknown_stats() {
+        let stats = receive("RESULT uptime(5) cache_hits(10)\r\n").unwrap();
+        assert_eq!(stats.uptime, Duration::from_secs(5));
+        assert_eq!(stats.commands_total, 0);
+    }
+
+    #[test]
+    fn should_reject_invalid_stats() {
+        match receive("RESULT uptime(long)\r\n") {
+            Err(Error::WrongResponse { .. }) => {}
+            _ => unreachable!(),
+        }
+        match receive("OK\r\n") {
+            Err(Error::WrongResponse { .. }) => {}
+            _ => unreachable!(),
+        }
+    }
+}
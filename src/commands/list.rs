@@ -63,7 +63,11 @@ impl StreamCommand for ListCommand {
         if let protocol::Response::Event(protocol::EventKind::List, _id, words) = res {
             Ok(words)
         } else {
-            Err(Error::WrongResponse)
+            Err(Error::unexpected_response())
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
 }
@@ -16,7 +16,11 @@ impl StreamCommand for PingCommand {
         if matches!(res, protocol::Response::Pong) {
             Ok(())
         } else {
-            Err(Error::WrongResponse)
+            Err(Error::unexpected_response())
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
 }
@@ -1,6 +1,7 @@
+use super::split::{request_len, split_text, SplitCommand};
 use super::StreamCommand;
 use crate::misc::ObjDest;
-use crate::protocol;
+use crate::protocol::{self, Protocol};
 use crate::result::*;
 
 /// Parameters for the `pop` command.
@@ -48,7 +49,33 @@ impl StreamCommand for PopCommand {
         if let protocol::Response::Result(count) = res {
             Ok(count)
         } else {
-            Err(Error::WrongResponse)
+            Err(Error::unexpected_response())
         }
     }
 }
+
+impl SplitCommand for PopCommand {
+    fn split(mut self, protocol: Protocol, max_buffer_size: usize) -> Vec<Self> {
+        if request_len(protocol, self.request()) <= max_buffer_size {
+            return vec![self];
+        }
+
+        let text = std::mem::take(&mut self.req.text);
+        let overhead = request_len(protocol, self.request());
+        let budget = max_buffer_size.saturating_sub(overhead);
+        if budget == 0 {
+            self.req.text = text;
+            return vec![self];
+        }
+
+        split_text(&text, budget)
+            .into_iter()
+            .map(|text| PopCommand {
+                req: PopRequest {
+                    dest: self.req.dest.clone(),
+                    text,
+                },
+            })
+            .collect()
+    }
+}
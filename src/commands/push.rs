@@ -1,6 +1,7 @@
+use super::split::{request_len, split_text, SplitCommand};
 use super::StreamCommand;
 use crate::misc::ObjDest;
-use crate::protocol;
+use crate::protocol::{self, Protocol};
 use crate::result::*;
 
 /// Parameters for the `push` command.
@@ -44,9 +45,7 @@ impl StreamCommand for PushCommand {
 
         let lang = req
             .lang
-            .or_else(|| {
-                whatlang::detect(&req.text).and_then(|i| (i.confidence() == 1.0).then(|| i.lang()))
-            })
+            .or_else(|| detect_lang(&req.text))
             .map(|l| l.code());
 
         protocol::Request::Push {
@@ -67,7 +66,87 @@ impl StreamCommand for PushCommand {
         if matches!(res, protocol::Response::Ok) {
             Ok(())
         } else {
-            Err(Error::WrongResponse)
+            Err(Error::unexpected_response())
+        }
+    }
+}
+
+impl SplitCommand for PushCommand {
+    fn split(mut self, protocol: Protocol, max_buffer_size: usize) -> Vec<Self> {
+        if request_len(protocol, self.request()) <= max_buffer_size {
+            return vec![self];
+        }
+
+        // The language is detected once for the whole text, because the short
+        // chunks may be detected differently.
+        let req = &mut self.req;
+        req.lang = req.lang.or_else(|| detect_lang(&req.text));
+
+        let text = std::mem::take(&mut req.text);
+        let mut overhead = request_len(protocol, self.request());
+        if self.req.lang.is_none() {
+            // A chunk can still be detected by itself.
+            overhead += " LANG(xxx)".len();
+        }
+
+        let budget = max_buffer_size.saturating_sub(overhead);
+        if budget == 0 {
+            self.req.text = text;
+            return vec![self];
+        }
+
+        split_text(&text, budget)
+            .into_iter()
+            .map(|text| PushCommand {
+                req: PushRequest {
+                    dest: self.req.dest.clone(),
+                    text,
+                    lang: self.req.lang,
+                },
+            })
+            .collect()
+    }
+}
+
+fn detect_lang(text: &str) -> Option<whatlang::Lang> {
+    whatlang::detect(text).and_then(|i| (i.confidence() == 1.0).then(|| i.lang()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dest;
+
+    fn push(text: &str) -> PushCommand {
+        PushCommand {
+            req: PushRequest::new(Dest::col("search").obj("recipe:1"), text),
         }
     }
+
+    #[test]
+    fn should_not_split_short_text() {
+        let chunks = push("Sweet Teriyaki Beef Skewers").split(Protocol::default(), 20000);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].req.text, "Sweet Teriyaki Beef Skewers");
+        assert_eq!(chunks[0].req.lang, None);
+    }
+
+    #[test]
+    fn should_split_text_to_fit_buffer() {
+        let text = "Sweet \"Teriyaki\" Beef Skewers with rice and vegetables ".repeat(100);
+        let chunks = push(&text).split(Protocol::default(), 200);
+        assert!(chunks.len() > 1);
+
+        for chunk in &chunks {
+            assert!(request_len(Protocol::default(), chunk.request()) <= 200);
+            assert_eq!(chunk.req.lang, detect_lang(&text));
+        }
+
+        let joined = chunks
+            .iter()
+            .map(|chunk| chunk.req.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(joined, text.trim_end());
+    }
 }
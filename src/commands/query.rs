@@ -94,7 +94,11 @@ impl StreamCommand for QueryCommand {
         if let protocol::Response::Event(protocol::EventKind::Query, _id, objects) = res {
             Ok(objects)
         } else {
-            Err(Error::WrongResponse)
+            Err(Error::unexpected_response())
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
 }
@@ -16,7 +16,7 @@ impl StreamCommand for QuitCommand {
         if matches!(res, protocol::Response::Ended) {
             Ok(())
         } else {
-            Err(Error::WrongResponse)
+            Err(Error::unexpected_response())
         }
     }
 }
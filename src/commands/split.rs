@@ -0,0 +1,87 @@
+use super::StreamCommand;
+use crate::protocol::{self, escaped_char_len, Protocol};
+
+/// Command with the text, which is sent as several commands if the request
+/// doesn't fit the buffer of the server.
+pub(crate) trait SplitCommand: StreamCommand + Sized {
+    fn split(self, protocol: Protocol, max_buffer_size: usize) -> Vec<Self>;
+}
+
+/// Returns the length of the formatted request, including the line ending.
+pub(crate) fn request_len(protocol: Protocol, req: protocol::Request) -> usize {
+    protocol.format_request(req).map_or(0, |buf| buf.len())
+}
+
+/// Splits the text on whitespace into parts of at most `max_len` bytes after
+/// escaping.
+///
+/// Words are joined with a single space. A word longer than `max_len` is split
+/// on the UTF-8 character boundaries.
+pub(crate) fn split_text(text: &str, max_len: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for word in text.split_whitespace() {
+        let word_len = word.chars().map(escaped_char_len).sum::<usize>();
+        let sep_len = usize::from(!current.is_empty());
+        if current_len + sep_len + word_len <= max_len {
+            if sep_len > 0 {
+                current.push(' ');
+            }
+            current.push_str(word);
+            current_len += sep_len + word_len;
+            continue;
+        }
+
+        if !current.is_empty() {
+            parts.push(std::mem::take(&mut current));
+        }
+        current_len = 0;
+
+        // A character which doesn't fit at all is sent as is.
+        for c in word.chars() {
+            let len = escaped_char_len(c);
+            if current_len + len > max_len && !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+                current_len = 0;
+            }
+            current.push(c);
+            current_len += len;
+        }
+    }
+
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_split_text_on_words() {
+        assert_eq!(
+            split_text("Sweet Teriyaki  Beef\nSkewers", 14),
+            vec!["Sweet Teriyaki", "Beef Skewers"]
+        );
+        assert_eq!(split_text("Beef", 4), vec!["Beef"]);
+        assert!(split_text("   ", 4).is_empty());
+    }
+
+    #[test]
+    fn should_split_long_words_on_char_boundaries() {
+        assert_eq!(split_text("abcdefg hi", 3), vec!["abc", "def", "g", "hi"]);
+        assert_eq!(split_text("привет", 5), vec!["пр", "ив", "ет"]);
+        assert_eq!(split_text("ф", 1), vec!["ф"]);
+    }
+
+    #[test]
+    fn should_split_on_escaped_length() {
+        assert_eq!(split_text("\"a\" b", 5), vec!["\"a\"", "b"]);
+        assert_eq!(split_text("a\\ b", 4), vec!["a\\", "b"]);
+    }
+}
@@ -57,7 +57,11 @@ impl StreamCommand for SuggestCommand {
         if let protocol::Response::Event(protocol::EventKind::Suggest, _id, words) = res {
             Ok(words)
         } else {
-            Err(Error::WrongResponse)
+            Err(Error::unexpected_response())
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
 }
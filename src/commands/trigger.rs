@@ -41,7 +41,7 @@ impl StreamCommand for TriggerCommand<'_> {
         if matches!(res, protocol::Response::Ok) {
             Ok(())
         } else {
-            Err(Error::WrongResponse)
+            Err(Error::unexpected_response())
         }
     }
 }
@@ -0,0 +1,179 @@
+use crate::result::*;
+use std::str::FromStr;
+
+/// Maximum length of the collection, bucket and object identifiers in bytes.
+pub const MAX_IDENTIFIER_LEN: usize = 256;
+
+/// Checks that the value can be sent as a single argument of the sonic command.
+///
+/// Sonic splits the command on whitespace, so the identifier cannot contain
+/// whitespace, quotes or control characters.
+pub(crate) fn validate_identifier(kind: &str, value: &str) -> Result<()> {
+    let reason = if value.is_empty() {
+        "is empty"
+    } else if value.len() > MAX_IDENTIFIER_LEN {
+        "is too long"
+    } else if value.contains(char::is_whitespace) {
+        "contains whitespace"
+    } else if value.contains('"') {
+        "contains quotes"
+    } else if value.contains(char::is_control) {
+        "contains control characters"
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::InvalidIdentifier(format!(
+        "{} `{}` {}",
+        kind,
+        value.escape_debug(),
+        reason
+    )))
+}
+
+macro_rules! identifier {
+    (
+        $(#[$outer:meta])*
+        $name:ident, $kind:literal
+    ) => {
+        $(#[$outer])*
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(String);
+
+        impl $name {
+            #[doc = concat!("Creates a new ", $kind, " if the value is a valid identifier.")]
+            pub fn new(value: impl Into<String>) -> Result<Self> {
+                let value = value.into();
+                validate_identifier($kind, &value)?;
+                Ok(Self(value))
+            }
+
+            #[doc = concat!("Returns the ", $kind, " as a string slice.")]
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            #[doc = concat!("Returns the ", $kind, " as a string.")]
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self> {
+                Self::new(s)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = Error;
+
+            fn try_from(value: String) -> Result<Self> {
+                Self::new(value)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = Error;
+
+            fn try_from(value: &str) -> Result<Self> {
+                Self::new(value)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+identifier!(
+    /// Name of the sonic collection, which is validated at construction.
+    ///
+    /// ```rust
+    /// # use sonic_channel::*;
+    /// let collection = Collection::new("wiki").unwrap();
+    /// assert_eq!(collection.as_str(), "wiki");
+    /// assert!(Collection::new("my wiki").is_err());
+    /// ```
+    Collection, "collection"
+);
+
+identifier!(
+    /// Name of the bucket in the collection, which is validated at construction.
+    ///
+    /// ```rust
+    /// # use sonic_channel::*;
+    /// let bucket: Bucket = "user:1".parse().unwrap();
+    /// assert_eq!(bucket.as_str(), "user:1");
+    /// assert!("\"user\"".parse::<Bucket>().is_err());
+    /// ```
+    Bucket, "bucket"
+);
+
+identifier!(
+    /// Id of the object in the bucket, which is validated at construction.
+    ///
+    /// ```rust
+    /// # use sonic_channel::*;
+    /// let object = ObjectId::try_from("article:1").unwrap();
+    /// assert_eq!(object.as_str(), "article:1");
+    /// assert!(ObjectId::try_from("article 1").is_err());
+    /// ```
+    ObjectId, "object id"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_accept_valid_identifiers() {
+        assert!(Collection::new("wiki").is_ok());
+        assert!(Bucket::new("user:1").is_ok());
+        assert!(ObjectId::new("статья/42").is_ok());
+        assert!(ObjectId::new("a".repeat(MAX_IDENTIFIER_LEN)).is_ok());
+    }
+
+    #[test]
+    fn should_reject_invalid_identifiers() {
+        for value in ["", "my obj", "tab\tobj", "\"obj\"", "obj\u{0}"] {
+            match ObjectId::new(value) {
+                Err(Error::InvalidIdentifier(_)) => {}
+                _ => unreachable!(),
+            }
+        }
+
+        match Collection::new("a".repeat(MAX_IDENTIFIER_LEN + 1)) {
+            Err(Error::InvalidIdentifier(message)) => assert!(message.ends_with("is too long")),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn should_describe_invalid_identifier() {
+        match ObjectId::new("my obj") {
+            Err(err) => assert_eq!(
+                err.to_string(),
+                "Invalid identifier: object id `my obj` contains whitespace"
+            ),
+            _ => unreachable!(),
+        }
+    }
+}
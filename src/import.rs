@@ -0,0 +1,436 @@
+//! Bulk import of the documents from JSONL or CSV files to the sonic index.
+//!
+//! **Note:** This module requires enabling the `import` feature and Rust 1.65
+//! or newer.
+
+use crate::result::*;
+use crate::{ChannelBuilder, IngestChannel};
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod document;
+pub use document::*;
+
+/// Default number of the parallel connections of the [`Importer`].
+pub const DEFAULT_IMPORT_CONNECTIONS: usize = 4;
+
+/// Number of the documents waiting for the free connection per connection.
+const QUEUE_SIZE_PER_CONNECTION: usize = 16;
+
+/// Progress of the running import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportProgress {
+    /// Number of the pushed documents.
+    pub imported: u64,
+    /// Number of the documents without text, which aren't pushed.
+    pub skipped: u64,
+    /// Time since the import started.
+    pub elapsed: Duration,
+}
+
+/// Result of the finished import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Number of the pushed documents.
+    pub imported: u64,
+    /// Number of the documents without text, which aren't pushed.
+    pub skipped: u64,
+    /// Number of the documents imported before, which are skipped because of
+    /// the checkpoint.
+    pub resumed_from: u64,
+    /// Duration of the import.
+    pub elapsed: Duration,
+}
+
+/// Error of the failed import.
+#[derive(Debug)]
+pub struct ImportError {
+    /// The reason of the failure.
+    pub error: Error,
+    /// The checkpoint file which was written to resume the import, if any.
+    pub checkpoint: Option<PathBuf>,
+}
+
+impl ImportError {
+    fn new(error: Error) -> Self {
+        Self {
+            error,
+            checkpoint: None,
+        }
+    }
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<ImportError> for Error {
+    fn from(err: ImportError) -> Self {
+        err.error
+    }
+}
+
+type ProgressHandler = Box<dyn Fn(&ImportProgress) + Send + Sync>;
+
+/// Pushes the documents from JSONL or CSV to the index by several ingest
+/// channels in parallel.
+///
+/// Every document is pushed by [`IngestChannel::push`], so large texts are
+/// split into several commands by the buffer size of the server.
+///
+/// If the import fails, the checkpoint file is written with the number of the
+/// documents which are imported from the beginning of the input. The next
+/// import with the same checkpoint file skips these documents, and the
+/// checkpoint file is removed when the import succeeds. Documents which were
+/// pushed after the checkpoint are pushed again, which doesn't change the index.
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # use sonic_channel::import::*;
+/// # fn main() -> result::Result<()> {
+/// let builder = ChannelBuilder::new("localhost:1491", "SecretPassword");
+/// let report = Importer::new(builder)
+///     .mapping(
+///         FieldMapping::default()
+///             .collection(FieldSource::value("recipes"))
+///             .text(["title", "description"]),
+///     )
+///     .connections(8)
+///     .checkpoint("recipes.checkpoint")
+///     .on_progress(|progress| eprintln!("imported {}", progress.imported))
+///     .import_file("recipes.jsonl")?;
+///
+/// println!("imported {} documents", report.imported);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Importer {
+    builder: ChannelBuilder,
+    mapping: FieldMapping,
+    connections: usize,
+    checkpoint: Option<PathBuf>,
+    on_progress: Option<ProgressHandler>,
+}
+
+impl std::fmt::Debug for Importer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Importer")
+            .field("builder", &self.builder)
+            .field("mapping", &self.mapping)
+            .field("connections", &self.connections)
+            .field("checkpoint", &self.checkpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Importer {
+    /// Creates the importer which starts ingest channels by the builder.
+    pub fn new(builder: ChannelBuilder) -> Self {
+        Self {
+            builder,
+            mapping: FieldMapping::default(),
+            connections: DEFAULT_IMPORT_CONNECTIONS,
+            checkpoint: None,
+            on_progress: None,
+        }
+    }
+
+    /// Sets the fields of the documents.
+    pub fn mapping(mut self, mapping: FieldMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    /// Sets the number of the parallel connections. The default is
+    /// [`DEFAULT_IMPORT_CONNECTIONS`].
+    pub fn connections(mut self, connections: usize) -> Self {
+        self.connections = connections.max(1);
+        self
+    }
+
+    /// Sets the checkpoint file to resume the failed import.
+    pub fn checkpoint(mut self, path: impl AsRef<Path>) -> Self {
+        self.checkpoint = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the handler which is called after every imported document.
+    pub fn on_progress(
+        mut self,
+        handler: impl Fn(&ImportProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Box::new(handler));
+        self
+    }
+
+    /// Imports the documents from the file. The format is detected by the
+    /// file extension, see [`ImportFormat::from_path`].
+    pub fn import_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> std::result::Result<ImportReport, ImportError> {
+        let path = path.as_ref();
+        let format = ImportFormat::from_path(path).ok_or_else(|| {
+            ImportError::new(Error::InvalidOptions(format!(
+                "cannot detect format of `{}` by the extension",
+                path.display()
+            )))
+        })?;
+        let file = File::open(path).map_err(|err| ImportError::new(Error::ReadDocuments(err)))?;
+        self.import(file, format)
+    }
+
+    /// Imports the documents from the reader.
+    ///
+    /// The error tells whether the checkpoint was written, so the import can
+    /// be resumed.
+    pub fn import(
+        &self,
+        reader: impl Read + Send,
+        format: ImportFormat,
+    ) -> std::result::Result<ImportReport, ImportError> {
+        let resumed_from = match &self.checkpoint {
+            Some(path) => load_checkpoint(path).map_err(ImportError::new)?,
+            None => 0,
+        };
+        let documents = read_documents(reader, format, &self.mapping).map_err(ImportError::new)?;
+
+        let started = Instant::now();
+        let mut tracker = Tracker::new(resumed_from);
+        let failure = self.run(documents, resumed_from, |outcome| {
+            tracker.complete(outcome);
+            if let Some(on_progress) = &self.on_progress {
+                on_progress(&ImportProgress {
+                    imported: tracker.imported,
+                    skipped: tracker.skipped,
+                    elapsed: started.elapsed(),
+                });
+            }
+        });
+
+        if let Some(err) = failure {
+            let mut err = ImportError::new(err);
+            if let Some(path) = &self.checkpoint {
+                match save_checkpoint(path, tracker.next) {
+                    Ok(()) => err.checkpoint = Some(path.clone()),
+                    Err(save_err) => log::warn!("[import] cannot write checkpoint: {}", save_err),
+                }
+            }
+            return Err(err);
+        }
+
+        if let Some(path) = &self.checkpoint {
+            match fs::remove_file(path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                    log::warn!("[import] cannot remove checkpoint: {}", err)
+                }
+                _ => {}
+            }
+        }
+
+        Ok(ImportReport {
+            imported: tracker.imported,
+            skipped: tracker.skipped,
+            resumed_from,
+            elapsed: started.elapsed(),
+        })
+    }
+
+    /// Pushes the documents after the checkpoint by the workers. Returns the
+    /// first error, after which the import stops.
+    fn run(
+        &self,
+        documents: Documents<'_>,
+        resumed_from: u64,
+        mut on_complete: impl FnMut(Outcome),
+    ) -> Option<Error> {
+        let stopped = AtomicBool::new(false);
+        let (job_tx, job_rx) = mpsc::sync_channel(self.connections * QUEUE_SIZE_PER_CONNECTION);
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let (outcome_tx, outcome_rx) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..self.connections {
+                let job_rx = job_rx.clone();
+                let outcome_tx = outcome_tx.clone();
+                let (builder, stopped) = (&self.builder, &stopped);
+                scope.spawn(move || push_documents(builder, &job_rx, &outcome_tx, stopped));
+            }
+            // The queue must be closed when all workers stop.
+            drop(job_rx);
+
+            let outcome_tx_reader = outcome_tx.clone();
+            let stopped_reader = &stopped;
+            scope.spawn(move || {
+                for (index, document) in (0u64..).zip(documents) {
+                    if stopped_reader.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if index < resumed_from {
+                        continue;
+                    }
+                    match document {
+                        Ok(document) => {
+                            if job_tx.send((index, document)).is_err() {
+                                break;
+                            }
+                        }
+                        Err(err) => {
+                            let _ = outcome_tx_reader.send(Outcome::Failed(err));
+                            break;
+                        }
+                    }
+                }
+            });
+            // The outcomes end when the reader and all workers stop.
+            drop(outcome_tx);
+
+            let mut failure = None;
+            for outcome in outcome_rx {
+                match outcome {
+                    Outcome::Failed(err) => {
+                        stopped.store(true, Ordering::SeqCst);
+                        failure.get_or_insert(err);
+                    }
+                    outcome => on_complete(outcome),
+                }
+            }
+            failure
+        })
+    }
+}
+
+/// Outcome of the document sent by the worker.
+enum Outcome {
+    Imported(u64),
+    Skipped(u64),
+    Failed(Error),
+}
+
+fn push_documents(
+    builder: &ChannelBuilder,
+    jobs: &Mutex<Receiver<(u64, Document)>>,
+    outcomes: &mpsc::Sender<Outcome>,
+    stopped: &AtomicBool,
+) {
+    let channel = match builder.start::<IngestChannel>() {
+        Ok(channel) => channel,
+        Err(err) => {
+            let _ = outcomes.send(Outcome::Failed(err));
+            return;
+        }
+    };
+
+    while !stopped.load(Ordering::SeqCst) {
+        let job = jobs.lock().unwrap_or_else(PoisonError::into_inner).recv();
+        let Ok((index, document)) = job else { break };
+
+        let outcome = if document.text.trim().is_empty() {
+            Outcome::Skipped(index)
+        } else {
+            match channel.push(document.into_request()) {
+                Ok(_) => Outcome::Imported(index),
+                Err(err) => Outcome::Failed(err),
+            }
+        };
+        if outcomes.send(outcome).is_err() {
+            break;
+        }
+    }
+    let _ = channel.quit();
+}
+
+/// Tracks the documents which are complete from the beginning of the input,
+/// while the workers complete them in any order.
+#[derive(Debug)]
+struct Tracker {
+    /// Index of the first incomplete document.
+    next: u64,
+    /// Complete documents after the first incomplete one.
+    completed: BTreeSet<u64>,
+    imported: u64,
+    skipped: u64,
+}
+
+impl Tracker {
+    fn new(next: u64) -> Self {
+        Self {
+            next,
+            completed: BTreeSet::new(),
+            imported: 0,
+            skipped: 0,
+        }
+    }
+
+    fn complete(&mut self, outcome: Outcome) {
+        let index = match outcome {
+            Outcome::Imported(index) => {
+                self.imported += 1;
+                index
+            }
+            Outcome::Skipped(index) => {
+                self.skipped += 1;
+                index
+            }
+            Outcome::Failed(_) => return,
+        };
+
+        self.completed.insert(index);
+        while self.completed.remove(&self.next) {
+            self.next += 1;
+        }
+    }
+}
+
+/// Reads the number of the imported documents, or 0 if there is no checkpoint.
+fn load_checkpoint(path: &Path) -> Result<u64> {
+    match fs::read_to_string(path) {
+        Ok(content) => content.trim().parse().map_err(|_| {
+            Error::InvalidOptions(format!("invalid checkpoint file `{}`", path.display()))
+        }),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(err) => Err(Error::ReadDocuments(err)),
+    }
+}
+
+fn save_checkpoint(path: &Path, imported: u64) -> io::Result<()> {
+    fs::write(path, format!("{}\n", imported))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_track_documents_complete_from_beginning() {
+        let mut tracker = Tracker::new(10);
+        tracker.complete(Outcome::Imported(11));
+        tracker.complete(Outcome::Skipped(12));
+        assert_eq!(tracker.next, 10);
+
+        tracker.complete(Outcome::Imported(10));
+        assert_eq!(tracker.next, 13);
+
+        tracker.complete(Outcome::Imported(15));
+        tracker.complete(Outcome::Failed(Error::timeout(
+            io::ErrorKind::TimedOut.into(),
+        )));
+        assert_eq!(tracker.next, 13);
+        assert_eq!((tracker.imported, tracker.skipped), (3, 1));
+    }
+}
@@ -0,0 +1,411 @@
+use crate::result::*;
+use crate::{Dest, PushRequest};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use whatlang::Lang;
+
+/// Format of the documents to import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// One JSON object per line.
+    Jsonl,
+
+    /// Comma-separated values with the header row, which names the fields.
+    Csv,
+}
+
+impl ImportFormat {
+    /// Detects the format by the file extension: `.jsonl` or `.ndjson` for
+    /// JSONL and `.csv` for CSV.
+    ///
+    /// The `.json` files usually contain a single JSON document, so they aren't
+    /// detected, and the JSONL format must be set explicitly.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Source of the collection or bucket of the imported document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldSource {
+    /// Takes the value from the field of the document.
+    Field(String),
+
+    /// Uses the same value for all documents.
+    Value(String),
+}
+
+impl FieldSource {
+    /// Takes the value from the field of the document.
+    pub fn field(name: impl ToString) -> Self {
+        Self::Field(name.to_string())
+    }
+
+    /// Uses the same value for all documents.
+    pub fn value(value: impl ToString) -> Self {
+        Self::Value(value.to_string())
+    }
+}
+
+/// Fields of the imported documents, which contain the destination and the
+/// text to push.
+///
+/// By default, the collection is taken from the `collection` field, the bucket
+/// is `default`, the object id is taken from the `id` field and the text is
+/// taken from the `text` field.
+///
+/// ```rust
+/// # use sonic_channel::import::*;
+/// let mapping = FieldMapping::default()
+///     .collection(FieldSource::value("recipes"))
+///     .bucket(FieldSource::field("user_id"))
+///     .object("recipe_id")
+///     .text(["title", "description"])
+///     .lang("lang");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMapping {
+    collection: FieldSource,
+    bucket: FieldSource,
+    object: String,
+    text: Vec<String>,
+    lang: Option<String>,
+}
+
+impl Default for FieldMapping {
+    fn default() -> Self {
+        Self {
+            collection: FieldSource::field("collection"),
+            bucket: FieldSource::value("default"),
+            object: String::from("id"),
+            text: vec![String::from("text")],
+            lang: None,
+        }
+    }
+}
+
+impl FieldMapping {
+    /// Sets the source of the collection.
+    pub fn collection(mut self, source: FieldSource) -> Self {
+        self.collection = source;
+        self
+    }
+
+    /// Sets the source of the bucket.
+    pub fn bucket(mut self, source: FieldSource) -> Self {
+        self.bucket = source;
+        self
+    }
+
+    /// Sets the field of the object id.
+    pub fn object(mut self, field: impl ToString) -> Self {
+        self.object = field.to_string();
+        self
+    }
+
+    /// Sets the fields of the text. The values of the fields are joined with
+    /// spaces, the missing fields are ignored.
+    pub fn text<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.text = fields.into_iter().map(|field| field.to_string()).collect();
+        self
+    }
+
+    /// Sets the field with the ISO 639-3 code of the text language, e.g. `eng`.
+    /// If the field is missing, the language is detected by the server.
+    pub fn lang(mut self, field: impl ToString) -> Self {
+        self.lang = Some(field.to_string());
+        self
+    }
+
+    /// Maps the record to the document by the function which returns the
+    /// value of the field.
+    fn document(
+        &self,
+        get: impl Fn(&str) -> Option<String>,
+    ) -> std::result::Result<Document, String> {
+        let required = |field: &str| {
+            get(field)
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| format!("missing `{}` field", field))
+        };
+        let source = |source: &FieldSource| match source {
+            FieldSource::Field(field) => required(field),
+            FieldSource::Value(value) => Ok(value.clone()),
+        };
+
+        let lang = match &self.lang {
+            Some(field) => match get(field).filter(|code| !code.is_empty()) {
+                Some(code) => Some(
+                    Lang::from_code(&code)
+                        .ok_or_else(|| format!("unknown language code `{}`", code))?,
+                ),
+                None => None,
+            },
+            None => None,
+        };
+
+        let text = self
+            .text
+            .iter()
+            .filter_map(|field| get(field))
+            .filter(|text| !text.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Ok(Document {
+            collection: source(&self.collection)?,
+            bucket: source(&self.bucket)?,
+            object: required(&self.object)?,
+            text,
+            lang,
+        })
+    }
+}
+
+/// The document to push to the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    /// Collection of the document.
+    pub collection: String,
+    /// Bucket of the document.
+    pub bucket: String,
+    /// Object id of the document.
+    pub object: String,
+    /// Text of the document. It may be empty if the mapped fields are missing.
+    pub text: String,
+    /// Language of the text.
+    pub lang: Option<Lang>,
+}
+
+impl Document {
+    pub(crate) fn into_request(self) -> PushRequest {
+        let dest = Dest::col_buc(self.collection, self.bucket).obj(self.object);
+        let req = PushRequest::new(dest, self.text);
+        match self.lang {
+            Some(lang) => req.lang(lang),
+            None => req,
+        }
+    }
+}
+
+pub(crate) type Documents<'a> = Box<dyn Iterator<Item = Result<Document>> + Send + 'a>;
+
+/// Reads the documents in the format. Blank lines of JSONL are skipped.
+pub(crate) fn read_documents<'a>(
+    reader: impl Read + Send + 'a,
+    format: ImportFormat,
+    mapping: &'a FieldMapping,
+) -> Result<Documents<'a>> {
+    match format {
+        ImportFormat::Jsonl => Ok(Box::new(read_jsonl(reader, mapping))),
+        ImportFormat::Csv => read_csv(reader, mapping),
+    }
+}
+
+fn read_jsonl<'a>(
+    reader: impl Read + Send + 'a,
+    mapping: &'a FieldMapping,
+) -> impl Iterator<Item = Result<Document>> + Send + 'a {
+    BufReader::new(reader)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(move |(n, line)| {
+            let line = line.map_err(Error::ReadDocuments)?;
+            let invalid =
+                |message: String| Error::InvalidDocument(format!("line {}: {}", n + 1, message));
+
+            let value: serde_json::Value =
+                serde_json::from_str(&line).map_err(|err| invalid(err.to_string()))?;
+            let object = value
+                .as_object()
+                .ok_or_else(|| invalid(String::from("expected JSON object")))?;
+            mapping
+                .document(|field| object.get(field).and_then(json_to_string))
+                .map_err(invalid)
+        })
+}
+
+/// Converts the JSON value to the text. Arrays are joined with spaces.
+fn json_to_string(value: &serde_json::Value) -> Option<String> {
+    use serde_json::Value;
+    match value {
+        Value::Null | Value::Object(_) => None,
+        Value::String(value) => Some(value.clone()),
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::Array(values) => {
+            let values = values.iter().filter_map(json_to_string).collect::<Vec<_>>();
+            Some(values.join(" "))
+        }
+    }
+}
+
+fn read_csv<'a>(reader: impl Read + Send + 'a, mapping: &'a FieldMapping) -> Result<Documents<'a>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers().map_err(csv_error)?.clone();
+
+    let documents = reader.into_records().map(move |record| {
+        let record = record.map_err(csv_error)?;
+        let line = record.position().map_or(0, |position| position.line());
+        mapping
+            .document(|field| {
+                let index = headers.iter().position(|header| header == field)?;
+                record.get(index).map(String::from)
+            })
+            .map_err(|message| Error::InvalidDocument(format!("line {}: {}", line, message)))
+    });
+    Ok(Box::new(documents))
+}
+
+fn csv_error(err: csv::Error) -> Error {
+    let line = err.position().map(|position| position.line());
+    match err.into_kind() {
+        csv::ErrorKind::Io(err) => Error::ReadDocuments(err),
+        kind => {
+            let message = match kind {
+                csv::ErrorKind::Utf8 { err, .. } => err.to_string(),
+                csv::ErrorKind::UnequalLengths {
+                    expected_len, len, ..
+                } => format!("expected {} fields, got {}", expected_len, len),
+                _ => String::from("cannot parse CSV"),
+            };
+            match line {
+                Some(line) => Error::InvalidDocument(format!("line {}: {}", line, message)),
+                None => Error::InvalidDocument(message),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(input: &str, format: ImportFormat, mapping: &FieldMapping) -> Vec<Result<Document>> {
+        read_documents(input.as_bytes(), format, mapping)
+            .unwrap()
+            .collect()
+    }
+
+    fn document(object: &str, text: &str) -> Document {
+        Document {
+            collection: String::from("recipes"),
+            bucket: String::from("default"),
+            object: object.to_string(),
+            text: text.to_string(),
+            lang: None,
+        }
+    }
+
+    #[test]
+    fn should_detect_format_by_extension() {
+        assert_eq!(
+            ImportFormat::from_path("a.jsonl"),
+            Some(ImportFormat::Jsonl)
+        );
+        assert_eq!(
+            ImportFormat::from_path("a.NDJSON"),
+            Some(ImportFormat::Jsonl)
+        );
+        assert_eq!(
+            ImportFormat::from_path("dir/a.csv"),
+            Some(ImportFormat::Csv)
+        );
+        assert_eq!(ImportFormat::from_path("a.json"), None);
+        assert_eq!(ImportFormat::from_path("a.txt"), None);
+        assert_eq!(ImportFormat::from_path("csv"), None);
+    }
+
+    #[test]
+    fn should_read_jsonl_documents() {
+        let mapping = FieldMapping::default()
+            .collection(FieldSource::value("recipes"))
+            .text(["title", "tags"]);
+        let input = r#"{"id": 1, "title": "Beef skewers", "tags": ["grill", "beef"]}
+
+{"id": "recipe:2", "title": "Teriyaki"}
+"#;
+        let documents = read(input, ImportFormat::Jsonl, &mapping);
+        assert_eq!(documents.len(), 2);
+        assert_eq!(
+            documents[0].as_ref().unwrap(),
+            &document("1", "Beef skewers grill beef")
+        );
+        assert_eq!(
+            documents[1].as_ref().unwrap(),
+            &document("recipe:2", "Teriyaki")
+        );
+    }
+
+    #[test]
+    fn should_report_line_of_invalid_jsonl_document() {
+        let mapping = FieldMapping::default();
+        let input = "{\"collection\": \"recipes\", \"id\": 1}\n[1]\n{\"id\": 3}\n{";
+        let documents = read(input, ImportFormat::Jsonl, &mapping);
+
+        assert_eq!(documents[0].as_ref().unwrap(), &document("1", ""));
+        let messages = documents[1..]
+            .iter()
+            .map(|document| match document {
+                Err(Error::InvalidDocument(message)) => message.clone(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(messages[0], "line 2: expected JSON object");
+        assert_eq!(messages[1], "line 3: missing `collection` field");
+        assert!(messages[2].starts_with("line 4: "));
+    }
+
+    #[test]
+    fn should_read_csv_documents() {
+        let mapping = FieldMapping::default()
+            .collection(FieldSource::field("col"))
+            .bucket(FieldSource::field("user"))
+            .text(["title", "body"])
+            .lang("lang");
+        let input = "col,user,id,title,body,lang\n\
+                     recipes,user:1,recipe:1,Beef,\"Skewers, grilled\",eng\n\
+                     recipes,user:2,recipe:2,Borscht,,\n";
+        let documents = read(input, ImportFormat::Csv, &mapping);
+
+        let first = documents[0].as_ref().unwrap();
+        assert_eq!(first.bucket, "user:1");
+        assert_eq!(first.text, "Beef Skewers, grilled");
+        assert_eq!(first.lang, Some(Lang::Eng));
+
+        let second = documents[1].as_ref().unwrap();
+        assert_eq!(second.object, "recipe:2");
+        assert_eq!(second.text, "Borscht");
+        assert_eq!(second.lang, None);
+    }
+
+    #[test]
+    fn should_report_line_of_invalid_csv_document() {
+        let mapping = FieldMapping::default().lang("lang");
+        let input = "collection,id,text,lang\nrecipes,1,Beef\nrecipes,2,Beef,xxx\n";
+        let documents = read(input, ImportFormat::Csv, &mapping);
+
+        match &documents[0] {
+            Err(Error::InvalidDocument(message)) => {
+                assert_eq!(message, "line 2: expected 4 fields, got 3")
+            }
+            _ => unreachable!(),
+        }
+        match &documents[1] {
+            Err(Error::InvalidDocument(message)) => {
+                assert_eq!(message, "line 3: unknown language code `xxx`")
+            }
+            _ => unreachable!(),
+        }
+    }
+}
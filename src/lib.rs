@@ -72,7 +72,35 @@
 //! }
 //! ```
 //!
+//! ### Async channels
+//!
+//! Note: This example requires enabling the `async` feature.
+//!
+//! Every channel has an asynchronous version built on top of the [tokio] runtime.
+//! They accept the same requests as the blocking channels.
+//!
+//! ```rust,no_run
+//! use sonic_channel::*;
+//!
+//! #[tokio::main]
+//! async fn main() -> result::Result<()> {
+//!     let channel = AsyncSearchChannel::start(
+//!         "localhost:1491",
+//!         "SecretPassword",
+//!     ).await?;
+//!
+//!     let objects = channel.query(QueryRequest::new(
+//!         Dest::col_buc("collection", "bucket"),
+//!         "recipe",
+//!     )).await?;
+//!     dbg!(objects);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
 //! [sonic]: https://github.com/valeriansaliou/sonic
+//! [tokio]: https://tokio.rs
 
 // Rustc lints.
 #![deny(
@@ -93,20 +121,37 @@ compile_error!(
 
 #[macro_use]
 mod macroses;
+mod identifier;
 mod misc;
 
 pub(crate) mod protocol;
 
 mod channels;
 
+#[cfg(feature = "async")]
+mod async_channels;
+
+mod pool;
+
 /// Contains the request parameters for each command to the sonic server.
 pub mod commands;
 
 /// Contains sonic channel error type and custom Result type for easy configure your functions.
 pub mod result;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "import")]
+#[clippy::msrv = "1.65"]
+pub mod import;
+
+#[cfg(feature = "async")]
+pub use async_channels::*;
 pub use channels::*;
 pub use commands::*;
+pub use identifier::*;
 pub use misc::*;
+pub use pool::*;
 
 pub use whatlang::Lang;
@@ -1,7 +1,7 @@
 macro_rules! init_command {
     (
         $(#[$outer:meta])*
-        use $cmd_name:ident
+        use $cmd_name:ident $(<$($cmd_lt:lifetime),+>)?
         for fn $fn_name:ident $(<$($lt:lifetime)+>)? (
             $($arg_name:ident : $arg_type:ty $( => $arg_value:expr)?,)*
         )
@@ -12,10 +12,33 @@ macro_rules! init_command {
             &self,
             $($arg_name: $arg_type),*
         ) -> $crate::result::Result<
-            <$cmd_name as $crate::commands::StreamCommand>::Response,
+            <$cmd_name $(<$($cmd_lt),+>)? as $crate::commands::StreamCommand>::Response,
         > {
             let command = $cmd_name { $($arg_name $(: $arg_value)?,)* };
             self.stream().run_command(command)
         }
     };
 }
+
+#[cfg(feature = "async")]
+macro_rules! init_async_command {
+    (
+        $(#[$outer:meta])*
+        use $cmd_name:ident $(<$($cmd_lt:lifetime),+>)?
+        for fn $fn_name:ident $(<$($lt:lifetime)+>)? (
+            $($arg_name:ident : $arg_type:ty $( => $arg_value:expr)?,)*
+        )
+        $(;)?
+    ) => {
+        $(#[$outer])*
+        pub async fn $fn_name $(<$($lt)+>)? (
+            &self,
+            $($arg_name: $arg_type),*
+        ) -> $crate::result::Result<
+            <$cmd_name $(<$($cmd_lt),+>)? as $crate::commands::StreamCommand>::Response,
+        > {
+            let command = $cmd_name { $($arg_name $(: $arg_value)?,)* };
+            self.stream().run_command(command).await
+        }
+    };
+}
@@ -1,3 +1,6 @@
+use crate::identifier::*;
+use crate::result::*;
+
 /// Search data destination. Contains collection, bucket and object.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ObjDest(Dest, String);
@@ -37,6 +40,10 @@ impl ObjDest {
 }
 
 /// Search objects destination. Contains collection and bucket.
+///
+/// The collection and bucket are checked before the command is sent to the
+/// server. Use the `try_*` builders or the [`Collection`] and [`Bucket`] types
+/// to check them earlier.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Dest {
     collection: String,
@@ -83,6 +90,49 @@ impl Dest {
         self
     }
 
+    /// Creates a new destination with collection. Returns an error if the
+    /// collection isn't a valid identifier.
+    ///
+    /// ```rust
+    /// # use sonic_channel::Dest;
+    /// assert!(Dest::try_col("wiki").is_ok());
+    /// assert!(Dest::try_col("my wiki").is_err());
+    /// ```
+    pub fn try_col(c: impl ToString) -> Result<Self> {
+        Ok(Self::col(Collection::new(c.to_string())?))
+    }
+
+    /// Creates a new destination with collection and bucket. Returns an error
+    /// if the collection or bucket isn't a valid identifier.
+    ///
+    /// ```rust
+    /// # use sonic_channel::Dest;
+    /// assert!(Dest::try_col_buc("wiki", "user:1").is_ok());
+    /// assert!(Dest::try_col_buc("wiki", "user 1").is_err());
+    /// ```
+    pub fn try_col_buc(c: impl ToString, b: impl ToString) -> Result<Self> {
+        Self::try_col(c)?.try_buc(b)
+    }
+
+    /// Set bucket for the destination. Returns an error if the bucket isn't a
+    /// valid identifier.
+    pub fn try_buc(self, b: impl ToString) -> Result<Self> {
+        Ok(self.buc(Bucket::new(b.to_string())?))
+    }
+
+    /// Set object id to the destination and transform to object destination
+    /// (`ObjDest`). Returns an error if the object id isn't a valid identifier.
+    ///
+    /// ```rust
+    /// # use sonic_channel::Dest;
+    /// let dest = Dest::col_buc("wiki", "user:1");
+    /// assert!(dest.clone().try_obj("article:1").is_ok());
+    /// assert!(dest.try_obj("article 1").is_err());
+    /// ```
+    pub fn try_obj(self, o: impl ToString) -> Result<ObjDest> {
+        Ok(self.obj(ObjectId::new(o.to_string())?))
+    }
+
     /// Set object id to the destination and transform to object destination (`ObjDest`).
     ///
     /// Short for `ObjDest::new(dest, object_id)`
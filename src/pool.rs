@@ -0,0 +1,364 @@
+use std::collections::VecDeque;
+use std::ops::Deref;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::channels::{ChannelBuilder, SonicChannel};
+use crate::commands::PingCommand;
+use crate::result::*;
+
+/// Configuration of the connection pool.
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// The number of connections which the pool tries to keep open. Broken and
+    /// discarded connections are replaced on the next checkout.
+    pub min_size: usize,
+    /// The maximum number of connections, including the checked out ones.
+    pub max_size: usize,
+    /// Idle connections above the `min_size` are closed after this timeout.
+    pub idle_timeout: Option<Duration>,
+    /// How long to wait for a connection if all of them are checked out.
+    /// If None, waits forever.
+    pub checkout_timeout: Option<Duration>,
+    /// Ping idle connection before checkout and replace it if the server doesn't answer.
+    pub test_on_checkout: bool,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            min_size: 0,
+            max_size: 10,
+            idle_timeout: Some(Duration::from_secs(60)),
+            checkout_timeout: Some(Duration::from_secs(30)),
+            test_on_checkout: true,
+        }
+    }
+}
+
+impl PoolConfig {
+    /// Set the minimum number of open connections.
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Set the maximum number of open connections.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Set the timeout after which idle connections are closed.
+    pub fn idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Set the timeout of waiting for an available connection.
+    pub fn checkout_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.checkout_timeout = timeout;
+        self
+    }
+
+    /// Enable or disable the `ping` of idle connections before checkout.
+    pub fn test_on_checkout(mut self, test: bool) -> Self {
+        self.test_on_checkout = test;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct IdleChannel<C> {
+    channel: C,
+    since: Instant,
+}
+
+#[derive(Debug)]
+struct PoolState<C> {
+    idle: VecDeque<IdleChannel<C>>,
+    /// The number of open connections, including the checked out ones.
+    size: usize,
+}
+
+/// Pool of started sonic channels which can be shared between threads.
+///
+/// The pool keeps the connections open after use, so the next checkout doesn't
+/// need to connect and run the start command again.
+///
+/// ```rust,no_run
+/// # use sonic_channel::*;
+/// # fn main() -> result::Result<()> {
+/// let pool = Pool::<SearchChannel>::new(
+///     "localhost:1491",
+///     "SecretPassword",
+///     PoolConfig::default().min_size(2).max_size(16),
+/// )?;
+///
+/// let channel = pool.get()?;
+/// let objects = channel.query(QueryRequest::new(Dest::col("search"), "Beef"))?;
+/// dbg!(objects);
+/// // the connection returns to the pool when `channel` is dropped.
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Pool<C: SonicChannel> {
+    builder: ChannelBuilder,
+    config: PoolConfig,
+    state: Mutex<PoolState<C>>,
+    released: Condvar,
+}
+
+impl<C> Pool<C>
+where
+    C: SonicChannel<Channel = C>,
+{
+    /// Creates a new pool and opens `min_size` connections.
+    pub fn new(addr: impl ToString, password: impl ToString, config: PoolConfig) -> Result<Self> {
+        Self::with_builder(ChannelBuilder::new(addr, password), config)
+    }
+
+    /// Creates a new pool which starts channels using the builder, and opens
+    /// `min_size` connections.
+    ///
+    /// ```rust,no_run
+    /// # use sonic_channel::*;
+    /// # fn main() -> result::Result<()> {
+    /// let builder = ChannelBuilder::new("localhost:1491", "SecretPassword")
+    ///     .reconnect(ReconnectPolicy::disabled());
+    /// let pool = Pool::<SearchChannel>::with_builder(builder, PoolConfig::default())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_builder(builder: ChannelBuilder, config: PoolConfig) -> Result<Self> {
+        let pool = Self {
+            builder,
+            state: Mutex::new(PoolState {
+                idle: VecDeque::with_capacity(config.max_size),
+                size: 0,
+            }),
+            released: Condvar::new(),
+            config,
+        };
+
+        for _ in 0..pool.config.min_size.min(pool.config.max_size) {
+            let channel = pool.connect()?;
+            let mut state = pool.lock_state();
+            state.size += 1;
+            state.idle.push_back(IdleChannel {
+                channel,
+                since: Instant::now(),
+            });
+        }
+
+        Ok(pool)
+    }
+
+    /// Checks out a channel from the pool.
+    ///
+    /// Returns an idle channel, opens a new connection if the pool isn't full, or
+    /// waits until any channel is returned to the pool. Then opens connections
+    /// until there are `min_size` of them again.
+    pub fn get(&self) -> Result<PooledChannel<'_, C>> {
+        let channel = self.checkout()?;
+        self.refill();
+        Ok(self.wrap(channel))
+    }
+
+    fn checkout(&self) -> Result<C> {
+        let deadline = self.config.checkout_timeout.map(|t| Instant::now() + t);
+
+        loop {
+            let mut state = self.lock_state();
+            self.close_expired(&mut state);
+
+            if let Some(idle) = state.idle.pop_back() {
+                drop(state);
+                if !self.config.test_on_checkout || Self::is_healthy(&idle.channel) {
+                    return Ok(idle.channel);
+                }
+
+                log::debug!("[pool] close broken connection");
+                self.release_slot();
+                continue;
+            }
+
+            if state.size < self.config.max_size {
+                state.size += 1;
+                drop(state);
+                return match self.connect() {
+                    Err(err) => {
+                        self.release_slot();
+                        Err(err)
+                    }
+                    ok => ok,
+                };
+            }
+
+            self.wait_released(state, deadline)?;
+        }
+    }
+
+    /// Returns the number of open connections, including the checked out ones.
+    pub fn size(&self) -> usize {
+        self.lock_state().size
+    }
+
+    /// Returns the number of idle connections.
+    pub fn idle(&self) -> usize {
+        self.lock_state().idle.len()
+    }
+
+    fn connect(&self) -> Result<C> {
+        self.builder.start()
+    }
+
+    /// Opens idle connections until there are `min_size` of them, including
+    /// the checked out ones. Errors are only logged, the next checkout tries
+    /// again.
+    fn refill(&self) {
+        let min_size = self.config.min_size.min(self.config.max_size);
+        loop {
+            let mut state = self.lock_state();
+            if state.size >= min_size {
+                return;
+            }
+            state.size += 1;
+            drop(state);
+
+            match self.connect() {
+                Ok(channel) => self.checkin(channel),
+                Err(err) => {
+                    log::warn!("[pool] cannot open connection: {}", err);
+                    self.release_slot();
+                    return;
+                }
+            }
+        }
+    }
+
+    fn is_healthy(channel: &C) -> bool {
+        channel.stream().run_command(PingCommand).is_ok()
+    }
+
+    fn wrap(&self, channel: C) -> PooledChannel<'_, C> {
+        PooledChannel {
+            pool: self,
+            channel: Some(channel),
+        }
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, PoolState<C>> {
+        // The state is always consistent between the operations, so it's safe to
+        // continue after a panic in another thread.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn close_expired(&self, state: &mut PoolState<C>) {
+        let idle_timeout = match self.config.idle_timeout {
+            Some(timeout) => timeout,
+            None => return,
+        };
+
+        // The oldest connections are at the front of the queue.
+        while state.size > self.config.min_size {
+            match state.idle.front() {
+                Some(idle) if idle.since.elapsed() >= idle_timeout => {
+                    state.idle.pop_front();
+                    state.size -= 1;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn release_slot(&self) {
+        self.lock_state().size -= 1;
+        self.released.notify_one();
+    }
+
+    fn wait_released(
+        &self,
+        state: MutexGuard<'_, PoolState<C>>,
+        deadline: Option<Instant>,
+    ) -> Result<()> {
+        match deadline {
+            None => {
+                drop(self.released.wait(state));
+                Ok(())
+            }
+            Some(deadline) => {
+                let timeout = deadline
+                    .checked_duration_since(Instant::now())
+                    .ok_or(Error::PoolTimeout)?;
+                let (_state, res) = self
+                    .released
+                    .wait_timeout(state, timeout)
+                    .unwrap_or_else(PoisonError::into_inner);
+                if res.timed_out() {
+                    Err(Error::PoolTimeout)
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    fn checkin(&self, channel: C) {
+        self.lock_state().idle.push_back(IdleChannel {
+            channel,
+            since: Instant::now(),
+        });
+        self.released.notify_one();
+    }
+}
+
+/// Sonic channel checked out from the [`Pool`]. The channel returns to the pool
+/// when it's dropped.
+#[derive(Debug)]
+pub struct PooledChannel<'a, C>
+where
+    C: SonicChannel<Channel = C>,
+{
+    pool: &'a Pool<C>,
+    channel: Option<C>,
+}
+
+impl<C> PooledChannel<'_, C>
+where
+    C: SonicChannel<Channel = C>,
+{
+    /// Closes the connection instead of returning it to the pool.
+    ///
+    /// Useful if you know that the connection is broken.
+    pub fn discard(mut self) {
+        if self.channel.take().is_some() {
+            self.pool.release_slot();
+        }
+    }
+}
+
+impl<C> Deref for PooledChannel<'_, C>
+where
+    C: SonicChannel<Channel = C>,
+{
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        self.channel
+            .as_ref()
+            .expect("Pooled channel is already released")
+    }
+}
+
+impl<C> Drop for PooledChannel<'_, C>
+where
+    C: SonicChannel<Channel = C>,
+{
+    fn drop(&mut self) {
+        if let Some(channel) = self.channel.take() {
+            self.pool.checkin(channel);
+        }
+    }
+}
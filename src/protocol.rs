@@ -1,11 +1,11 @@
 use std::io::{self, BufWriter, Write};
 use std::{path::PathBuf, str::FromStr};
 
+use crate::identifier::validate_identifier;
 use crate::{result::*, ChannelMode};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy)]
 pub struct Protocol {
-    #[allow(dead_code)]
     version: Version,
 }
 
@@ -16,6 +16,10 @@ impl From<Version> for Protocol {
 }
 
 impl Protocol {
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
     pub fn format_request(&self, req: Request) -> io::Result<Vec<u8>> {
         let mut res = BufWriter::new(Vec::new());
 
@@ -24,6 +28,13 @@ impl Protocol {
 
             Request::Ping => write!(res, "PING")?,
 
+            Request::Info => write!(res, "INFO")?,
+
+            Request::Help(manual) => match manual {
+                Some(manual) => write!(res, "HELP {}", manual)?,
+                None => write!(res, "HELP")?,
+            },
+
             Request::Start { mode, password } => write!(res, "START {} {}", mode, password)?,
 
             #[rustfmt::skip]
@@ -44,12 +55,11 @@ impl Protocol {
 
             #[rustfmt::skip]
             Request::Pop { collection, bucket, object, terms } => {
-                write!(res, "POP {} {} {} \"{}\"", collection, bucket, object, terms)?
+                write!(res, "POP {} {} {} \"{}\"", collection, bucket, object, escape_text(&terms))?
             },
             #[rustfmt::skip]
             Request::Push { collection, bucket, object, terms, lang } => {
-                let oneline_terms = remove_multiline(&terms);
-                write!(res, "PUSH {} {} {} \"{}\"", collection, bucket, object, oneline_terms)?;
+                write!(res, "PUSH {} {} {} \"{}\"", collection, bucket, object, escape_text(&terms))?;
                 if let Some(lang) = lang {
                     write!(res, " LANG({})", lang)?
                 }
@@ -57,7 +67,7 @@ impl Protocol {
 
             #[rustfmt::skip]
             Request::Query { collection, bucket, terms, offset, limit, lang } => {
-                write!(res, "QUERY {} {} \"{}\"", collection, bucket, terms)?;
+                write!(res, "QUERY {} {} \"{}\"", collection, bucket, escape_text(&terms))?;
                 if let Some(limit) = limit {
                     write!(res, " LIMIT({})", limit)?;
                 }
@@ -70,7 +80,7 @@ impl Protocol {
             }
             #[rustfmt::skip]
             Request::Suggest { collection, bucket, word, limit } => {
-                write!(res, "SUGGEST {} {} \"{}\"", collection, bucket, word)?;
+                write!(res, "SUGGEST {} {} \"{}\"", collection, bucket, escape_text(&word))?;
                 if let Some(limit) = limit {
                     write!(res, " LIMIT({})", limit)?;
                 }
@@ -105,6 +115,13 @@ impl Protocol {
     }
 
     pub fn parse_response(&self, line: &str) -> Result<Response> {
+        self.parse_segments(line).map_err(|err| match err {
+            Error::WrongResponse { .. } => Error::wrong_response(line),
+            err => err,
+        })
+    }
+
+    fn parse_segments(&self, line: &str) -> Result<Response> {
         let mut segments = line.split_whitespace();
         match segments.next() {
             Some("STARTED") => match (segments.next(), segments.next(), segments.next()) {
@@ -114,47 +131,51 @@ impl Protocol {
                         max_buffer_size: parse_server_config(raw_buffer_size)?,
                     }))
                 }
-                _ => Err(Error::WrongResponse),
+                _ => Err(Error::unexpected_response()),
             },
             Some("PENDING") => {
                 let event_id = segments
                     .next()
                     .map(String::from)
-                    .ok_or(Error::WrongResponse)?;
+                    .ok_or_else(Error::unexpected_response)?;
                 Ok(Response::Pending(event_id))
             }
             Some("RESULT") => match segments.next() {
-                Some(num) => num
-                    .parse()
-                    .map(Response::Result)
-                    .map_err(|_| Error::WrongResponse),
-                _ => Err(Error::WrongResponse),
+                Some(num) => match num.parse() {
+                    Ok(num) => Ok(Response::Result(num)),
+                    Err(_) => {
+                        let raw = line.trim_start().strip_prefix("RESULT").unwrap_or_default();
+                        parse_result_values(raw).map(Response::Values)
+                    }
+                },
+                _ => Err(Error::unexpected_response()),
             },
             Some("EVENT") => {
                 let event_kind = match segments.next() {
                     Some("SUGGEST") => Ok(EventKind::Suggest),
                     Some("QUERY") => Ok(EventKind::Query),
                     Some("LIST") => Ok(EventKind::List),
-                    _ => Err(Error::WrongResponse),
+                    _ => Err(Error::unexpected_response()),
                 }?;
 
                 let event_id = segments
                     .next()
                     .map(String::from)
-                    .ok_or(Error::WrongResponse)?;
+                    .ok_or_else(Error::unexpected_response)?;
 
                 let objects = segments.map(String::from).collect();
 
                 Ok(Response::Event(event_kind, event_id, objects))
             }
             Some("OK") => Ok(Response::Ok),
+            Some("PONG") => Ok(Response::Pong),
             Some("ENDED") => Ok(Response::Ended),
-            Some("CONNECTED") => Ok(Response::Connected),
-            Some("ERR") => match segments.next() {
-                Some(message) => Err(Error::SonicServer(String::from(message))),
-                _ => Err(Error::WrongResponse),
-            },
-            _ => Err(Error::WrongResponse),
+            Some("CONNECTED") => Ok(Response::Connected(segments.collect::<Vec<_>>().join(" "))),
+            Some("ERR") => {
+                let message = line.trim_start().strip_prefix("ERR").unwrap_or_default();
+                Err(Error::SonicServer(message.parse()?))
+            }
+            _ => Err(Error::unexpected_response()),
         }
     }
 }
@@ -163,7 +184,7 @@ impl Protocol {
 // Primitives                                                                //
 //===========================================================================//
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Version {
     V1 = 1,
@@ -196,14 +217,27 @@ pub type EventId = String;
 pub enum Response {
     Ok,
     Ended,
-    Connected,
+    /// The banner of the server, e.g. `<sonic-server v1.4.0>`.
+    Connected(String),
     Pending(EventId),
     Pong,
     Started(StartedPayload),
     Result(usize),
+    /// Named values of the result, e.g. `uptime(12) clients_connected(1)`.
+    Values(Vec<(String, String)>),
     Event(EventKind, EventId, Vec<String>),
 }
 
+impl Response {
+    /// Returns the object ids of the query event.
+    pub fn query_objects_mut(&mut self) -> Option<&mut Vec<String>> {
+        match self {
+            Response::Event(EventKind::Query, _, objects) => Some(objects),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct StartedPayload {
     pub protocol_version: u8,
@@ -229,6 +263,8 @@ pub enum Request {
     },
     Quit,
     Ping,
+    Info,
+    Help(Option<String>),
     Trigger(TriggerRequest),
     Suggest {
         collection: String,
@@ -275,6 +311,100 @@ pub enum Request {
     },
 }
 
+impl Request {
+    /// Returns the name of the command, e.g. `QUERY`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Request::Start { .. } => "START",
+            Request::Quit => "QUIT",
+            Request::Ping => "PING",
+            Request::Info => "INFO",
+            Request::Help(_) => "HELP",
+            Request::Trigger(_) => "TRIGGER",
+            Request::Suggest { .. } => "SUGGEST",
+            Request::List { .. } => "LIST",
+            Request::Query { .. } => "QUERY",
+            Request::Push { .. } => "PUSH",
+            Request::Pop { .. } => "POP",
+            Request::Flush {
+                bucket: None,
+                object: None,
+                ..
+            } => "FLUSHC",
+            Request::Flush { object: None, .. } => "FLUSHB",
+            Request::Flush { .. } => "FLUSHO",
+            Request::Count { .. } => "COUNT",
+        }
+    }
+
+    /// Returns the object id of the request.
+    pub fn object_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Request::Push { object, .. } | Request::Pop { object, .. } => Some(object),
+            Request::Flush { object, .. } | Request::Count { object, .. } => object.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// Checks that the collection, bucket and object id can be sent as single
+    /// arguments of the command.
+    pub fn validate(&self) -> Result<()> {
+        let (collection, bucket, object) = match self {
+            Request::Suggest {
+                collection, bucket, ..
+            }
+            | Request::List {
+                collection, bucket, ..
+            }
+            | Request::Query {
+                collection, bucket, ..
+            } => (collection, Some(bucket), None),
+            Request::Push {
+                collection,
+                bucket,
+                object,
+                ..
+            }
+            | Request::Pop {
+                collection,
+                bucket,
+                object,
+                ..
+            } => (collection, Some(bucket), Some(object)),
+            Request::Flush {
+                collection,
+                bucket,
+                object,
+            }
+            | Request::Count {
+                collection,
+                bucket,
+                object,
+            } => (collection, bucket.as_ref(), object.as_ref()),
+            Request::Start { .. }
+            | Request::Quit
+            | Request::Ping
+            | Request::Info
+            | Request::Trigger(_) => return Ok(()),
+            Request::Help(manual) => {
+                return match manual {
+                    Some(manual) => validate_identifier("manual", manual),
+                    None => Ok(()),
+                }
+            }
+        };
+
+        validate_identifier("collection", collection)?;
+        if let Some(bucket) = bucket {
+            validate_identifier("bucket", bucket)?;
+        }
+        if let Some(object) = object {
+            validate_identifier("object id", object)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub enum TriggerRequest {
     Consolidate,
@@ -289,9 +419,66 @@ pub enum TriggerRequest {
 fn parse_server_config<T: FromStr>(raw: &str) -> Result<T> {
     raw.split_terminator(&['(', ')'])
         .nth(1)
-        .ok_or(Error::WrongResponse)?
+        .ok_or_else(Error::unexpected_response)?
         .parse()
-        .map_err(|_| Error::WrongResponse)
+        .map_err(|_| Error::unexpected_response())
+}
+
+/// Parses the named values of the result, e.g. `uptime(12) commands(QUERY, LIST)`.
+fn parse_result_values(mut raw: &str) -> Result<Vec<(String, String)>> {
+    let mut values = Vec::new();
+    loop {
+        raw = raw.trim_start();
+        if raw.is_empty() {
+            return Ok(values);
+        }
+
+        let (key, rest) = raw.split_once('(').ok_or_else(Error::unexpected_response)?;
+        let (value, rest) = rest
+            .split_once(')')
+            .ok_or_else(Error::unexpected_response)?;
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(Error::unexpected_response());
+        }
+
+        values.push((key.to_string(), value.to_string()));
+        raw = rest;
+    }
+}
+
+/// Escapes the text, so it can be sent between double quotes.
+///
+/// Sonic reads the text until the first unescaped quote, and only knows the
+/// `\"` escape, so the quotes are escaped, the control characters (including
+/// line breaks) are replaced with spaces, and a space is added after the
+/// trailing backslash, which would escape the closing quote otherwise.
+pub(crate) fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in remove_multiline(text).chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            c if c.is_control() => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+
+    if escaped.ends_with('\\') {
+        escaped.push(' ');
+    }
+
+    escaped
+}
+
+/// Returns the maximum length of the escaped character.
+///
+/// The backslash takes two bytes, because a space may be added after it.
+#[cfg(any(test, feature = "ingest"))]
+pub(crate) fn escaped_char_len(c: char) -> usize {
+    match c {
+        '"' | '\\' => 2,
+        c if c.is_control() => 1,
+        c => c.len_utf8(),
+    }
 }
 
 fn remove_multiline(text: &str) -> String {
@@ -310,6 +497,40 @@ fn remove_multiline(text: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    /// Reads the quoted text from the command parts like the sonic server does.
+    fn parse_quoted_text<'a>(parts: &mut impl Iterator<Item = &'a str>) -> Option<String> {
+        let mut raw = String::new();
+        for part in parts.by_ref() {
+            if !raw.is_empty() {
+                raw.push(' ');
+            }
+            raw.push_str(part);
+            if raw.len() > 1 && raw.ends_with('"') && !raw.ends_with("\\\"") {
+                break;
+            }
+        }
+
+        let inner = raw.strip_prefix('"')?.strip_suffix('"')?;
+        Some(inner.replace("\\\"", "\""))
+    }
+
+    fn format_push(terms: &str) -> String {
+        let req = Request::Push {
+            collection: String::from("search"),
+            bucket: String::from("default"),
+            object: String::from("recipe:1"),
+            terms: terms.to_string(),
+            lang: Some("eng"),
+        };
+        let buf = Protocol::default().format_request(req).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    fn words(text: &str) -> Vec<&str> {
+        text.split_whitespace().collect()
+    }
 
     #[test]
     fn should_parse_protocol() {
@@ -327,6 +548,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_parse_server_error() {
+        match Protocol::default().parse_response("ERR query_error(bad query) \r\n") {
+            Err(Error::SonicServer(ServerError::QueryError(detail))) => {
+                assert_eq!(detail, "bad query")
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn should_parse_pong() {
+        match Protocol::default().parse_response("PONG\r\n") {
+            Ok(Response::Pong) => {}
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn should_parse_result_values() {
+        match Protocol::default().parse_response("RESULT commands(QUERY, LIST) uptime(12)\r\n") {
+            Ok(Response::Values(values)) => assert_eq!(
+                values,
+                vec![
+                    (String::from("commands"), String::from("QUERY, LIST")),
+                    (String::from("uptime"), String::from("12")),
+                ]
+            ),
+            _ => unreachable!(),
+        }
+
+        match Protocol::default().parse_response("RESULT 42\r\n") {
+            Ok(Response::Result(42)) => {}
+            _ => unreachable!(),
+        }
+
+        match Protocol::default().parse_response("RESULT uptime(12\r\n") {
+            Err(Error::WrongResponse { line, .. }) => assert_eq!(line, "RESULT uptime(12"),
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn should_make_single_line() {
         let text = "
@@ -337,4 +600,54 @@ World
         let expected_text = "Hello World";
         assert_eq!(remove_multiline(text), expected_text);
     }
+
+    #[test]
+    fn should_escape_text() {
+        assert_eq!(escape_text("Beef"), "Beef");
+        assert_eq!(escape_text("say \"hi\""), "say \\\"hi\\\"");
+        assert_eq!(escape_text("tab\there\u{0}"), "tab here ");
+        assert_eq!(escape_text("C:\\"), "C:\\ ");
+    }
+
+    #[test]
+    fn should_escape_terms_of_all_commands() {
+        let line = format_push("a \"quoted\" word\\");
+        assert_eq!(
+            line,
+            "PUSH search default recipe:1 \"a \\\"quoted\\\" word\\ \" LANG(eng)\r\n"
+        );
+
+        let req = Request::Suggest {
+            collection: String::from("search"),
+            bucket: String::from("default"),
+            word: String::from("be\"ef"),
+            limit: None,
+        };
+        let buf = Protocol::default().format_request(req).unwrap();
+        assert_eq!(buf, b"SUGGEST search default \"be\\\"ef\"\r\n");
+    }
+
+    proptest! {
+        #[test]
+        fn should_round_trip_any_text(
+            text in prop_oneof![any::<String>(), "[a\"\\\\ \n\t\r]*"],
+        ) {
+            let line = format_push(&text);
+            let line = line.strip_suffix("\r\n").unwrap();
+            prop_assert!(!line.contains(['\r', '\n']));
+
+            let mut parts = line.split_whitespace().skip(4);
+            let parsed = parse_quoted_text(&mut parts).unwrap_or_default();
+            prop_assert_eq!(parts.collect::<Vec<_>>(), vec!["LANG(eng)"]);
+
+            let expected = text.replace(|c: char| c.is_control(), " ");
+            prop_assert_eq!(words(&parsed), words(&expected));
+        }
+
+        #[test]
+        fn should_not_exceed_escaped_length(text in any::<String>()) {
+            let max_len = text.chars().map(escaped_char_len).sum::<usize>();
+            prop_assert!(escape_text(&text).len() <= max_len);
+        }
+    }
 }
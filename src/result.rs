@@ -1,23 +1,36 @@
 use crate::channels::ChannelMode;
+use std::io;
 
 /// Sugar if you expect only sonic-channel error type in result
 pub type Result<T> = std::result::Result<T, Error>;
 
-/// Wrap for sonic channel error kind. This type has std::error::Error
-/// implementation and you can use boxed trait for catch other errors
-/// like this.
+// Wrap for sonic channel error kind. This type has std::error::Error
+// implementation and you can use boxed trait for catch other errors
+// like this.
 
 /// All error kinds that you can see in sonic-channel crate.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// Cannot connect to the sonic search backend.
-    ConnectToServer,
+    ConnectToServer(io::Error),
 
     /// Cannot write message to stream.
-    WriteToStream,
+    WriteToStream {
+        /// Name of the command which was written, e.g. `QUERY`, if known.
+        command: Option<&'static str>,
+        /// The I/O error of the stream.
+        source: io::Error,
+    },
 
     /// Cannot read message in stream.
-    ReadStream,
+    ReadStream {
+        /// Name of the command which waited for the response, e.g. `QUERY`, if
+        /// known.
+        command: Option<&'static str>,
+        /// The I/O error of the stream.
+        source: io::Error,
+    },
 
     /// Cannot switch channel mode from uninitialized.
     SwitchMode,
@@ -30,29 +43,79 @@ pub enum Error {
 
     /// Response from sonic server are wrong! Actually it may happen if you use
     /// unsupported sonic backend version. Please write issue to the github repo.
-    WrongResponse,
+    WrongResponse {
+        /// Name of the command which got the response, e.g. `QUERY`, if known.
+        command: Option<&'static str>,
+        /// The response line without the line ending, or an empty string if
+        /// it isn't known.
+        line: String,
+    },
 
     /// You cannot run the command in current channel.
     UnsupportedCommand((&'static str, Option<ChannelMode>)),
 
     /// This error appears if the error occurred on the server side
-    SonicServer(String),
+    SonicServer(ServerError),
+
+    /// There is no available connection in the pool after the checkout timeout.
+    PoolTimeout,
+
+    /// The connect, read or write operation didn't complete within the configured timeout.
+    Timeout {
+        /// Name of the command which was running, e.g. `QUERY`, if known.
+        command: Option<&'static str>,
+        /// The I/O error of the operation.
+        source: io::Error,
+    },
+
+    /// Invalid connection options, e.g. the connection URL cannot be parsed.
+    InvalidOptions(String),
+
+    /// Invalid TLS configuration or the TLS handshake with the server failed.
+    Tls(Box<dyn std::error::Error + Send + Sync>),
+
+    /// The collection, bucket or object id cannot be sent to the server, e.g.
+    /// it contains whitespace.
+    InvalidIdentifier(String),
+
+    /// Cannot read the documents to import.
+    ReadDocuments(io::Error),
+
+    /// The document to import cannot be parsed or misses the mapped field.
+    InvalidDocument(String),
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use Error::*;
         match self {
-            ConnectToServer => f.write_str("Cannot connect to server"),
-            WriteToStream => f.write_str("Cannot write data to stream"),
-            ReadStream => f.write_str("Cannot read sonic response from stream"),
+            ConnectToServer(_) => f.write_str("Cannot connect to server"),
+            WriteToStream { command, .. } => match command {
+                Some(command) => write!(f, "Cannot write {} command to stream", command),
+                None => f.write_str("Cannot write data to stream"),
+            },
+            ReadStream { command, .. } => match command {
+                Some(command) => write!(
+                    f,
+                    "Cannot read sonic response to {} command from stream",
+                    command
+                ),
+                None => f.write_str("Cannot read sonic response from stream"),
+            },
             SwitchMode => f.write_str("Cannot switch channel mode"),
             RunCommand => f.write_str("Cannot run command in current mode"),
             QueryResponse(message) => {
                 write!(f, "Error in query response: {}", message)
             }
-            WrongResponse => {
-                write!(f, "Client cannot parse response from sonic server. Please write an issue to github (https://github.com/pleshevskiy/sonic-channel).")
+            WrongResponse { command, line } => {
+                f.write_str("Client cannot parse response")?;
+                if let Some(command) = command {
+                    write!(f, " to {} command", command)?;
+                }
+                if !line.is_empty() {
+                    write!(f, " `{}`", line.escape_debug())?;
+                }
+                write!(f, " from sonic server. Please write an issue to github (https://github.com/pleshevskiy/sonic-channel).")
             }
             UnsupportedCommand((command_name, channel_mode)) => {
                 if let Some(channel_mode) = channel_mode {
@@ -69,9 +132,337 @@ impl std::fmt::Display for Error {
                     )
                 }
             }
-            SonicServer(message) => write!(f, "Sonic Server-side error: {}", message),
+            SonicServer(error) => write!(f, "Sonic Server-side error: {}", error),
+            PoolTimeout => f.write_str("Timed out waiting for an available connection in the pool"),
+            Timeout { command, .. } => match command {
+                Some(command) => write!(
+                    f,
+                    "Sonic server didn't respond to {} command in time",
+                    command
+                ),
+                None => f.write_str("Sonic server didn't respond in time"),
+            },
+            InvalidOptions(message) => write!(f, "Invalid connection options: {}", message),
+            Tls(err) => write!(f, "TLS error: {}", err),
+            InvalidIdentifier(message) => write!(f, "Invalid identifier: {}", message),
+            ReadDocuments(_) => f.write_str("Cannot read documents to import"),
+            InvalidDocument(message) => write!(f, "Invalid document: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ConnectToServer(err)
+            | Error::WriteToStream { source: err, .. }
+            | Error::ReadStream { source: err, .. }
+            | Error::Timeout { source: err, .. }
+            | Error::ReadDocuments(err) => Some(err),
+            Error::Tls(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// Creates the error of the failed write to the stream.
+    pub(crate) fn write_to_stream(source: io::Error) -> Self {
+        Error::WriteToStream {
+            command: None,
+            source,
+        }
+    }
+
+    /// Creates the error of the failed read from the stream.
+    pub(crate) fn read_stream(source: io::Error) -> Self {
+        Error::ReadStream {
+            command: None,
+            source,
+        }
+    }
+
+    /// Creates the error of the operation which didn't complete in time.
+    pub(crate) fn timeout(source: io::Error) -> Self {
+        Error::Timeout {
+            command: None,
+            source,
+        }
+    }
+
+    /// Creates the error of the unexpected response line.
+    pub(crate) fn wrong_response(line: &str) -> Self {
+        Error::WrongResponse {
+            command: None,
+            line: line.trim_end().to_string(),
+        }
+    }
+
+    /// Creates the error of the response which doesn't match the command. The
+    /// line is added by the stream.
+    pub(crate) fn unexpected_response() -> Self {
+        Self::wrong_response("")
+    }
+
+    /// Adds the command name to the stream and timeout errors, and the command
+    /// name and the response line to the wrong response error.
+    pub(crate) fn in_command(self, name: &'static str, response_line: &str) -> Self {
+        match self {
+            Error::WriteToStream { command, source } => Error::WriteToStream {
+                command: command.or(Some(name)),
+                source,
+            },
+            Error::ReadStream { command, source } => Error::ReadStream {
+                command: command.or(Some(name)),
+                source,
+            },
+            Error::Timeout { command, source } => Error::Timeout {
+                command: command.or(Some(name)),
+                source,
+            },
+            Error::WrongResponse { command, line } => Error::WrongResponse {
+                command: command.or(Some(name)),
+                line: if line.is_empty() {
+                    response_line.trim_end().to_string()
+                } else {
+                    line
+                },
+            },
+            err => err,
         }
     }
 }
 
-impl std::error::Error for Error {}
+/// Error returned by the sonic server in the `ERR <code>(<detail>)` response.
+///
+/// ```rust
+/// # use sonic_channel::result::ServerError;
+/// let error: ServerError = "invalid_format(PUSH <collection> <bucket>)".parse().unwrap();
+/// assert_eq!(error, ServerError::InvalidFormat(String::from("PUSH <collection> <bucket>")));
+/// assert_eq!(error.code(), "invalid_format");
+/// assert_eq!(error.detail(), Some("PUSH <collection> <bucket>"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ServerError {
+    /// The command has wrong arguments. Contains the expected format.
+    InvalidFormat(String),
+
+    /// The meta key of the command, e.g. `LIMIT`, isn't supported.
+    InvalidMetaKey(String),
+
+    /// The value of the meta argument, e.g. `LIMIT(abc)`, is wrong.
+    InvalidMetaValue(String),
+
+    /// The query cannot be executed.
+    QueryError(String),
+
+    /// The command was rejected by the server policy.
+    PolicyReject(String),
+
+    /// The command isn't known by the server.
+    UnknownCommand,
+
+    /// The command isn't recognized in the current mode.
+    NotRecognized,
+
+    /// The requested item isn't found.
+    NotFound,
+
+    /// The command line exceeds the buffer size of the server.
+    BufferOverflow,
+
+    /// Unexpected failure on the server side.
+    InternalError,
+
+    /// The server is shutting down and doesn't accept commands.
+    ShuttingDown,
+
+    /// Any other error, e.g. of the newer server version.
+    Other {
+        /// The error code, e.g. `not_allowed`.
+        code: String,
+        /// The error detail between the parentheses.
+        detail: Option<String>,
+    },
+}
+
+impl ServerError {
+    /// Returns the error code, e.g. `invalid_format`.
+    pub fn code(&self) -> &str {
+        use ServerError::*;
+        match self {
+            InvalidFormat(_) => "invalid_format",
+            InvalidMetaKey(_) => "invalid_meta_key",
+            InvalidMetaValue(_) => "invalid_meta_value",
+            QueryError(_) => "query_error",
+            PolicyReject(_) => "policy_reject",
+            UnknownCommand => "unknown_command",
+            NotRecognized => "not_recognized",
+            NotFound => "not_found",
+            BufferOverflow => "buffer_overflow",
+            InternalError => "internal_error",
+            ShuttingDown => "shutting_down",
+            Other { code, .. } => code,
+        }
+    }
+
+    /// Returns the error detail between the parentheses, if any.
+    pub fn detail(&self) -> Option<&str> {
+        use ServerError::*;
+        match self {
+            InvalidFormat(detail)
+            | InvalidMetaKey(detail)
+            | InvalidMetaValue(detail)
+            | QueryError(detail)
+            | PolicyReject(detail) => Some(detail),
+            Other { detail, .. } => detail.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.detail() {
+            Some(detail) => write!(f, "{}({})", self.code(), detail),
+            None => f.write_str(self.code()),
+        }
+    }
+}
+
+impl std::str::FromStr for ServerError {
+    type Err = Error;
+
+    fn from_str(message: &str) -> Result<Self> {
+        let message = message.trim();
+        let (code, detail) = match message.find(|c: char| c == '(' || c.is_whitespace()) {
+            Some(start) => {
+                let rest = message[start..].trim_start();
+                let detail = rest
+                    .strip_prefix('(')
+                    .map(|rest| rest.strip_suffix(')').unwrap_or(rest))
+                    .unwrap_or(rest);
+                (&message[..start], Some(detail.to_string()))
+            }
+            None => (message, None),
+        };
+
+        if code.is_empty() {
+            return Err(Error::unexpected_response());
+        }
+
+        use ServerError::*;
+        let with_detail =
+            |variant: fn(String) -> ServerError| variant(detail.clone().unwrap_or_default());
+        Ok(match code {
+            "invalid_format" => with_detail(InvalidFormat),
+            "invalid_meta_key" => with_detail(InvalidMetaKey),
+            "invalid_meta_value" => with_detail(InvalidMetaValue),
+            "query_error" => with_detail(QueryError),
+            "policy_reject" => with_detail(PolicyReject),
+            "unknown_command" => UnknownCommand,
+            "not_recognized" => NotRecognized,
+            "not_found" => NotFound,
+            "buffer_overflow" => BufferOverflow,
+            "internal_error" => InternalError,
+            "shutting_down" => ShuttingDown,
+            _ => Other {
+                code: code.to_string(),
+                detail,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_server_error_with_detail() {
+        let message =
+            "invalid_format(PUSH <collection> <bucket> <object> \"<text>\" [LANG(<locale>)]?)";
+        let error: ServerError = message.parse().unwrap();
+        assert_eq!(
+            error,
+            ServerError::InvalidFormat(String::from(
+                "PUSH <collection> <bucket> <object> \"<text>\" [LANG(<locale>)]?"
+            ))
+        );
+        assert_eq!(error.to_string(), message);
+    }
+
+    #[test]
+    fn should_parse_server_error_without_detail() {
+        assert_eq!(
+            "not_found".parse::<ServerError>().ok(),
+            Some(ServerError::NotFound)
+        );
+        assert_eq!(
+            "shutting_down".parse::<ServerError>().ok(),
+            Some(ServerError::ShuttingDown)
+        );
+        assert!("".parse::<ServerError>().is_err());
+    }
+
+    #[test]
+    fn should_keep_unknown_server_error() {
+        let error: ServerError = "not_allowed(QUERY in ingest mode)".parse().unwrap();
+        assert_eq!(error.code(), "not_allowed");
+        assert_eq!(error.detail(), Some("QUERY in ingest mode"));
+        assert_eq!(error.to_string(), "not_allowed(QUERY in ingest mode)");
+    }
+
+    #[test]
+    fn should_add_command_to_wrong_response() {
+        let error = Error::unexpected_response().in_command("QUERY", "OK\r\n");
+        match &error {
+            Error::WrongResponse { command, line } => {
+                assert_eq!(*command, Some("QUERY"));
+                assert_eq!(line, "OK");
+            }
+            _ => unreachable!(),
+        }
+        assert!(error
+            .to_string()
+            .starts_with("Client cannot parse response to QUERY command `OK` from sonic server."));
+    }
+
+    #[test]
+    fn should_chain_io_error_source() {
+        use std::error::Error as _;
+
+        let error = Error::read_stream(io::Error::new(io::ErrorKind::UnexpectedEof, "closed"));
+        assert_eq!(error.source().unwrap().to_string(), "closed");
+        let error = Error::timeout(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+        assert_eq!(error.source().unwrap().to_string(), "timed out");
+        let error = Error::Tls("no root certificates".into());
+        assert_eq!(error.source().unwrap().to_string(), "no root certificates");
+        assert!(Error::unexpected_response().source().is_none());
+    }
+
+    #[test]
+    fn should_add_command_to_stream_errors() {
+        let error = Error::read_stream(io::Error::new(io::ErrorKind::UnexpectedEof, "closed"))
+            .in_command("QUERY", "");
+        assert!(matches!(
+            error,
+            Error::ReadStream {
+                command: Some("QUERY"),
+                ..
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Cannot read sonic response to QUERY command from stream"
+        );
+
+        let error = Error::timeout(io::Error::new(io::ErrorKind::TimedOut, "timed out"))
+            .in_command("PUSH", "");
+        assert_eq!(
+            error.to_string(),
+            "Sonic server didn't respond to PUSH command in time"
+        );
+    }
+}
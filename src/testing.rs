@@ -0,0 +1,356 @@
+//! Utilities to test the code which uses sonic channels without a running
+//! sonic server.
+//!
+//! **Note:** This module requires enabling the `testing` feature.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+mod fake;
+pub use fake::*;
+
+mod replay;
+pub use replay::*;
+
+/// Default buffer size reported by the mock server, the same as in sonic.
+pub const MOCK_BUFFER_SIZE: usize = 20000;
+
+/// Scripted response of the [`MockSonicServer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockResponse {
+    /// Replies with `OK`.
+    Ok,
+
+    /// Replies with `RESULT <count>`.
+    Result(usize),
+
+    /// Replies with `PENDING <id>` and then `EVENT <COMMAND> <id> <items>`.
+    Event(Vec<String>),
+
+    /// Replies with `ERR <message>`, e.g. `query_error(bad query)`.
+    Err(String),
+
+    /// Replies with the lines as is. The line endings are added by the server.
+    Lines(Vec<String>),
+
+    /// Closes the connection without reply.
+    Close,
+}
+
+impl MockResponse {
+    /// Creates the event response with the object ids or words.
+    pub fn event<I, S>(items: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Event(items.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    /// Scripted responses by the command name, e.g. `QUERY`.
+    responses: HashMap<String, VecDeque<MockResponse>>,
+    /// Command lines received by the server without line endings.
+    received: Vec<String>,
+    /// Connected clients, which are closed when the server stops.
+    clients: Vec<TcpStream>,
+    password: Option<String>,
+    buffer_size: Option<usize>,
+    next_event_id: usize,
+    /// Index of the [`FakeSonic`] server.
+    index: Option<FakeIndex>,
+}
+
+/// In-process sonic server which listens on an ephemeral local port.
+///
+/// The server speaks the sonic protocol with the channels of all modes. It
+/// replies to every command with a default response, e.g. `PONG` for `PING`
+/// and an empty event for `QUERY`, unless the response is scripted with
+/// [`MockSonicServer::respond`]. All received commands are recorded, so tests
+/// can assert on them.
+///
+/// The server stops when it's dropped.
+///
+/// ```rust
+/// # use sonic_channel::*;
+/// # use sonic_channel::testing::*;
+/// # fn main() -> result::Result<()> {
+/// let server = MockSonicServer::start().unwrap();
+/// server.respond("QUERY", MockResponse::event(["recipe:1"]));
+///
+/// let channel = SearchChannel::start(server.addr(), "SecretPassword")?;
+/// let objects = channel.query(QueryRequest::new(Dest::col("search"), "Beef"))?;
+/// assert_eq!(objects, vec!["recipe:1"]);
+///
+/// assert!(server.received().contains(&String::from("QUERY search default \"Beef\"")));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MockSonicServer {
+    listener: LocalListener,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockSonicServer {
+    /// Starts the server on an ephemeral port of the local interface.
+    pub fn start() -> io::Result<Self> {
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let client_state = state.clone();
+        let listener = LocalListener::start(move |stream| {
+            if let Err(err) = serve(stream, &client_state) {
+                log::debug!("[mock] client error: {}", err);
+            }
+        })?;
+
+        Ok(Self { listener, state })
+    }
+
+    /// Returns the address of the server.
+    pub fn addr(&self) -> SocketAddr {
+        self.listener.addr
+    }
+
+    /// Requires the password in the `START` command. By default any password
+    /// is accepted.
+    pub fn require_password(&self, password: impl Into<String>) {
+        self.lock().password = Some(password.into());
+    }
+
+    /// Changes the buffer size reported to the new channels.
+    pub fn set_buffer_size(&self, buffer_size: usize) {
+        self.lock().buffer_size = Some(buffer_size);
+    }
+
+    /// Queues the response to the next command with the name, e.g. `QUERY`.
+    ///
+    /// Scripted responses are used in the order they're queued, after that the
+    /// server replies with the default response again.
+    pub fn respond(&self, command: &str, response: MockResponse) {
+        self.lock()
+            .responses
+            .entry(command.to_ascii_uppercase())
+            .or_default()
+            .push_back(response);
+    }
+
+    /// Returns the command lines received by the server, including `START`,
+    /// without line endings.
+    pub fn received(&self) -> Vec<String> {
+        self.lock().received.clone()
+    }
+
+    /// Returns the received command lines with the name, e.g. `PUSH`.
+    pub fn received_commands(&self, command: &str) -> Vec<String> {
+        self.lock()
+            .received
+            .iter()
+            .filter(|line| command_name(line).eq_ignore_ascii_case(command))
+            .cloned()
+            .collect()
+    }
+
+    /// Clears the received command lines.
+    pub fn clear_received(&self) {
+        self.lock().received.clear();
+    }
+
+    /// Closes connections of all clients, e.g. to test reconnects.
+    pub fn disconnect_clients(&self) {
+        for client in self.lock().clients.drain(..) {
+            let _ = client.shutdown(Shutdown::Both);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Drop for MockSonicServer {
+    fn drop(&mut self) {
+        self.disconnect_clients();
+    }
+}
+
+/// Listener on an ephemeral port of the local interface, which serves every
+/// client in its own thread until it's dropped.
+#[derive(Debug)]
+struct LocalListener {
+    addr: SocketAddr,
+    stopped: Arc<AtomicBool>,
+}
+
+impl LocalListener {
+    fn start(serve: impl Fn(TcpStream) + Send + Sync + 'static) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))?;
+        let addr = listener.local_addr()?;
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let serve = Arc::new(serve);
+        let accept_stopped = stopped.clone();
+        thread::Builder::new()
+            .name(String::from("sonic-mock-server"))
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if accept_stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(_) => continue,
+                    };
+
+                    let serve = serve.clone();
+                    let _ = thread::Builder::new()
+                        .name(String::from("sonic-mock-client"))
+                        .spawn(move || serve(stream));
+                }
+            })?;
+
+        Ok(Self { addr, stopped })
+    }
+}
+
+impl Drop for LocalListener {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wakes up the accept loop, so it can notice that the listener is stopped.
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+fn command_name(line: &str) -> &str {
+    line.split_whitespace().next().unwrap_or_default()
+}
+
+fn serve(stream: TcpStream, state: &Mutex<MockState>) -> io::Result<()> {
+    state
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .clients
+        .push(stream.try_clone()?);
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    writeln_crlf(&mut writer, "CONNECTED <sonic-server v1.4.9>")?;
+
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        let line = line.trim_end();
+        log::debug!("[mock] received {}", line);
+        let lines = {
+            let mut state = state.lock().unwrap_or_else(|err| err.into_inner());
+            state.received.push(line.to_string());
+            match reply(&mut state, line) {
+                Some(lines) => lines,
+                None => return writer.shutdown(Shutdown::Both),
+            }
+        };
+
+        for line in &lines {
+            writeln_crlf(&mut writer, line)?;
+        }
+        if command_name(line).eq_ignore_ascii_case("QUIT") {
+            return Ok(());
+        }
+    }
+}
+
+fn writeln_crlf(writer: &mut TcpStream, line: &str) -> io::Result<()> {
+    write!(writer, "{}\r\n", line)?;
+    writer.flush()
+}
+
+/// Returns the reply lines to the command, or `None` to close the connection.
+fn reply(state: &mut MockState, line: &str) -> Option<Vec<String>> {
+    let command = command_name(line).to_ascii_uppercase();
+
+    let response = state
+        .responses
+        .get_mut(&command)
+        .and_then(VecDeque::pop_front)
+        .unwrap_or_else(|| default_response(state, &command, line));
+
+    let lines = match response {
+        MockResponse::Ok => vec![String::from("OK")],
+        MockResponse::Result(count) => vec![format!("RESULT {}", count)],
+        MockResponse::Err(message) => vec![format!("ERR {}", message)],
+        MockResponse::Lines(lines) => lines,
+        MockResponse::Close => return None,
+        MockResponse::Event(items) => {
+            state.next_event_id += 1;
+            let event_id = format!("{:08x}", state.next_event_id);
+            let mut event = format!("EVENT {} {}", command, event_id);
+            for item in items {
+                event.push(' ');
+                event.push_str(&item);
+            }
+            vec![format!("PENDING {}", event_id), event]
+        }
+    };
+
+    Some(lines)
+}
+
+fn default_response(state: &mut MockState, command: &str, raw: &str) -> MockResponse {
+    if let Some(response) = state
+        .index
+        .as_mut()
+        .and_then(|index| index.handle(command, raw))
+    {
+        return response;
+    }
+
+    let mut args = raw.split_whitespace().skip(1);
+    let line = |line: &str| MockResponse::Lines(vec![line.to_string()]);
+    let err = |message: &str| MockResponse::Err(message.to_string());
+
+    match command {
+        "START" => {
+            let mode = args.next().unwrap_or_default();
+            let password = args.next().unwrap_or_default();
+            if !matches!(mode, "search" | "ingest" | "control") {
+                return err("invalid_mode");
+            }
+            if matches!(state.password.as_deref(), Some(p) if p != password) {
+                return err("authentication_failed");
+            }
+            let buffer_size = state.buffer_size.unwrap_or(MOCK_BUFFER_SIZE);
+            line(&format!(
+                "STARTED {} protocol(1) buffer_size({})",
+                mode, buffer_size
+            ))
+        }
+        "PING" => line("PONG"),
+        "QUIT" => line("ENDED quit"),
+        "QUERY" | "SUGGEST" | "LIST" => MockResponse::Event(Vec::new()),
+        "PUSH" | "TRIGGER" => MockResponse::Ok,
+        "POP" | "COUNT" | "FLUSHC" | "FLUSHB" | "FLUSHO" => MockResponse::Result(0),
+        "INFO" => line(
+            "RESULT uptime(0) clients_connected(1) commands_total(0) command_latency_best(0) \
+             command_latency_worst(0) kv_open_count(0) fst_open_count(0) fst_consolidate_count(0)",
+        ),
+        "HELP" => match args.next() {
+            None => line("RESULT manuals(commands)"),
+            Some("commands") => line(
+                "RESULT commands(QUERY, SUGGEST, LIST, PUSH, POP, COUNT, FLUSHC, FLUSHB, \
+                 FLUSHO, TRIGGER, INFO, PING, HELP, QUIT)",
+            ),
+            Some(_) => err("not_found"),
+        },
+        _ => err("unknown_command"),
+    }
+}
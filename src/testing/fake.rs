@@ -0,0 +1,442 @@
+use super::{MockResponse, MockSonicServer};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io;
+use std::net::SocketAddr;
+
+const QUERY_LIMIT_DEFAULT: usize = 10;
+const SUGGEST_LIMIT_DEFAULT: usize = 5;
+const LIST_LIMIT_DEFAULT: usize = 100;
+
+/// In-process sonic server with an in-memory index.
+///
+/// Unlike the [`MockSonicServer`] with default responses, the pushed text is
+/// indexed, so the search commands return meaningful results:
+///
+/// * the text is split on non-alphanumeric characters and lowercased,
+/// * `QUERY` returns the objects which contain all terms, the term matches
+///   words which start with it, the most recently pushed objects come first,
+/// * `SUGGEST` returns the words which start with the last term in
+///   alphabetical order,
+/// * `LIST` returns all words of the bucket in alphabetical order,
+/// * `COUNT` and `FLUSH*` commands count buckets of the collection, objects of
+///   the bucket or words of the object.
+///
+/// The scripted responses of the [`FakeSonic::server`] take priority over the
+/// index.
+///
+/// ```rust
+/// # use sonic_channel::*;
+/// # use sonic_channel::testing::*;
+/// # fn main() -> result::Result<()> {
+/// let fake = FakeSonic::start().unwrap();
+///
+/// let ingest_channel = IngestChannel::start(fake.addr(), "SecretPassword")?;
+/// let dest = Dest::col("recipes").obj("recipe:1");
+/// ingest_channel.push(PushRequest::new(dest, "Sweet Teriyaki Beef Skewers"))?;
+///
+/// let search_channel = SearchChannel::start(fake.addr(), "SecretPassword")?;
+/// let objects = search_channel.query(QueryRequest::new(Dest::col("recipes"), "beef"))?;
+/// assert_eq!(objects, vec!["recipe:1"]);
+///
+/// let words = search_channel.suggest(SuggestRequest::new(Dest::col("recipes"), "sk"))?;
+/// assert_eq!(words, vec!["skewers"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FakeSonic {
+    server: MockSonicServer,
+}
+
+impl FakeSonic {
+    /// Starts the server with an empty index on an ephemeral port of the local
+    /// interface.
+    pub fn start() -> io::Result<Self> {
+        let server = MockSonicServer::start()?;
+        server.lock().index = Some(FakeIndex::default());
+        Ok(Self { server })
+    }
+
+    /// Returns the address of the server.
+    pub fn addr(&self) -> SocketAddr {
+        self.server.addr()
+    }
+
+    /// Returns the underlying server to script responses or to assert on the
+    /// received commands.
+    pub fn server(&self) -> &MockSonicServer {
+        &self.server
+    }
+}
+
+#[derive(Debug, Default)]
+struct FakeObject {
+    words: BTreeSet<String>,
+    /// Sequence number of the last push, the most recent objects are found first.
+    pushed_at: u64,
+}
+
+type Bucket = BTreeMap<String, FakeObject>;
+type Collection = BTreeMap<String, Bucket>;
+
+/// In-memory index of the [`FakeSonic`].
+#[derive(Debug, Default)]
+pub(super) struct FakeIndex {
+    collections: BTreeMap<String, Collection>,
+    pushes: u64,
+}
+
+/// Command parsed like sonic does: the arguments, the quoted text and the
+/// meta values, e.g. `LIMIT(10)`.
+struct FakeCommand<'a> {
+    args: Vec<&'a str>,
+    text: Option<String>,
+    meta: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> FakeCommand<'a> {
+    fn parse(raw: &'a str) -> Option<Self> {
+        let (head, text, tail) = match raw.find('"') {
+            Some(start) => {
+                let (text, tail) = parse_quoted_text(&raw[start + 1..])?;
+                (&raw[..start], Some(text), tail)
+            }
+            None => (raw, None, ""),
+        };
+
+        let mut args = head.split_whitespace().collect::<Vec<_>>();
+        let mut meta = HashMap::new();
+        let meta_parts = match text {
+            Some(_) => tail.split_whitespace().collect(),
+            // Without the text the meta values follow the arguments.
+            None => {
+                let first_meta = args.iter().position(|arg| arg.ends_with(')'));
+                first_meta.map_or_else(Vec::new, |pos| args.split_off(pos))
+            }
+        };
+        for part in meta_parts {
+            let (key, value) = part.strip_suffix(')')?.split_once('(')?;
+            meta.insert(key, value);
+        }
+
+        Some(Self { args, text, meta })
+    }
+
+    fn arg(&self, index: usize) -> Option<&'a str> {
+        self.args.get(index).copied()
+    }
+
+    fn meta(&self, key: &str, default: usize) -> Option<usize> {
+        match self.meta.get(key) {
+            Some(value) => value.parse().ok(),
+            None => Some(default),
+        }
+    }
+}
+
+/// Reads the text until the unescaped quote and returns it with the rest of
+/// the line.
+fn parse_quoted_text(raw: &str) -> Option<(String, &str)> {
+    let mut text = String::new();
+    let mut chars = raw.char_indices();
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '"' => return Some((text, &raw[pos + 1..])),
+            '\\' if raw[pos + 1..].starts_with('"') => {
+                chars.next();
+                text.push('"');
+            }
+            c => text.push(c),
+        }
+    }
+    None
+}
+
+/// Splits the text on non-alphanumeric characters and lowercases the words.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn page<T>(items: impl Iterator<Item = T>, offset: usize, limit: usize) -> Vec<T> {
+    items.skip(offset).take(limit).collect()
+}
+
+impl FakeIndex {
+    /// Runs the index command, or returns `None` if the command isn't handled
+    /// by the index.
+    pub(super) fn handle(&mut self, command: &str, raw: &str) -> Option<MockResponse> {
+        if !matches!(
+            command,
+            "PUSH"
+                | "POP"
+                | "COUNT"
+                | "FLUSHC"
+                | "FLUSHB"
+                | "FLUSHO"
+                | "QUERY"
+                | "SUGGEST"
+                | "LIST"
+        ) {
+            return None;
+        }
+
+        let response = FakeCommand::parse(raw).and_then(|cmd| match command {
+            "PUSH" => self.push(&cmd),
+            "POP" => self.pop(&cmd),
+            "COUNT" => self.count(&cmd),
+            "FLUSHC" | "FLUSHB" | "FLUSHO" => self.flush(&cmd),
+            "QUERY" => self.query(&cmd),
+            "SUGGEST" => self.suggest(&cmd),
+            _ => self.list(&cmd),
+        });
+
+        Some(
+            response
+                .unwrap_or_else(|| MockResponse::Err(format!("invalid_format({})", raw.trim()))),
+        )
+    }
+
+    fn bucket(&self, cmd: &FakeCommand<'_>) -> Option<&Bucket> {
+        self.collections.get(cmd.arg(1)?)?.get(cmd.arg(2)?)
+    }
+
+    fn push(&mut self, cmd: &FakeCommand<'_>) -> Option<MockResponse> {
+        let (collection, bucket, object) = (cmd.arg(1)?, cmd.arg(2)?, cmd.arg(3)?);
+        let words = tokenize(cmd.text.as_deref()?);
+
+        self.pushes += 1;
+        let object = self
+            .collections
+            .entry(collection.to_string())
+            .or_default()
+            .entry(bucket.to_string())
+            .or_default()
+            .entry(object.to_string())
+            .or_default();
+        object.words.extend(words);
+        object.pushed_at = self.pushes;
+
+        Some(MockResponse::Ok)
+    }
+
+    fn pop(&mut self, cmd: &FakeCommand<'_>) -> Option<MockResponse> {
+        let (collection, bucket, object) = (cmd.arg(1)?, cmd.arg(2)?, cmd.arg(3)?);
+        let words = tokenize(cmd.text.as_deref()?);
+
+        let mut count = 0;
+        let objects = self
+            .collections
+            .get_mut(collection)
+            .and_then(|buckets| buckets.get_mut(bucket));
+        if let Some(objects) = objects {
+            if let Some(found) = objects.get_mut(object) {
+                count = words
+                    .iter()
+                    .filter(|word| found.words.remove(*word))
+                    .count();
+                if found.words.is_empty() {
+                    objects.remove(object);
+                }
+            }
+        }
+        self.remove_empty(collection, bucket);
+
+        Some(MockResponse::Result(count))
+    }
+
+    fn count(&self, cmd: &FakeCommand<'_>) -> Option<MockResponse> {
+        let collection = self.collections.get(cmd.arg(1)?);
+        let count = match (cmd.arg(2), cmd.arg(3)) {
+            (None, _) => collection.map_or(0, BTreeMap::len),
+            (Some(bucket), None) => collection
+                .and_then(|buckets| buckets.get(bucket))
+                .map_or(0, BTreeMap::len),
+            (Some(bucket), Some(object)) => collection
+                .and_then(|buckets| buckets.get(bucket))
+                .and_then(|objects| objects.get(object))
+                .map_or(0, |object| object.words.len()),
+        };
+
+        Some(MockResponse::Result(count))
+    }
+
+    fn flush(&mut self, cmd: &FakeCommand<'_>) -> Option<MockResponse> {
+        let collection = cmd.arg(1)?;
+        let count = match (cmd.arg(2), cmd.arg(3)) {
+            (None, _) => self
+                .collections
+                .remove(collection)
+                .map_or(0, |buckets| buckets.len()),
+            (Some(bucket), None) => self
+                .collections
+                .get_mut(collection)
+                .and_then(|buckets| buckets.remove(bucket))
+                .map_or(0, |objects| objects.len()),
+            (Some(bucket), Some(object)) => self
+                .collections
+                .get_mut(collection)
+                .and_then(|buckets| buckets.get_mut(bucket))
+                .and_then(|objects| objects.remove(object))
+                .map_or(0, |object| object.words.len()),
+        };
+        if let Some(bucket) = cmd.arg(2) {
+            self.remove_empty(collection, bucket);
+        }
+
+        Some(MockResponse::Result(count))
+    }
+
+    fn query(&self, cmd: &FakeCommand<'_>) -> Option<MockResponse> {
+        let terms = tokenize(cmd.text.as_deref()?);
+        let limit = cmd.meta("LIMIT", QUERY_LIMIT_DEFAULT)?;
+        let offset = cmd.meta("OFFSET", 0)?;
+
+        let mut found = match self.bucket(cmd) {
+            Some(objects) if !terms.is_empty() => objects
+                .iter()
+                .filter(|(_, object)| {
+                    terms
+                        .iter()
+                        .all(|term| object.words.iter().any(|word| word.starts_with(term)))
+                })
+                .collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+        found.sort_by_key(|(_, object)| Reverse(object.pushed_at));
+
+        let ids = found.into_iter().map(|(id, _)| id.clone());
+        Some(MockResponse::Event(page(ids, offset, limit)))
+    }
+
+    fn suggest(&self, cmd: &FakeCommand<'_>) -> Option<MockResponse> {
+        let terms = tokenize(cmd.text.as_deref()?);
+        let limit = cmd.meta("LIMIT", SUGGEST_LIMIT_DEFAULT)?;
+
+        let words = match (self.bucket(cmd), terms.last()) {
+            (Some(objects), Some(term)) => bucket_words(objects)
+                .into_iter()
+                .filter(|word| word.starts_with(term.as_str()))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Some(MockResponse::Event(page(words.into_iter(), 0, limit)))
+    }
+
+    fn list(&self, cmd: &FakeCommand<'_>) -> Option<MockResponse> {
+        let limit = cmd.meta("LIMIT", LIST_LIMIT_DEFAULT)?;
+        let offset = cmd.meta("OFFSET", 0)?;
+
+        let words = self.bucket(cmd).map(bucket_words).unwrap_or_default();
+        Some(MockResponse::Event(page(words.into_iter(), offset, limit)))
+    }
+
+    fn remove_empty(&mut self, collection: &str, bucket: &str) {
+        if let Some(buckets) = self.collections.get_mut(collection) {
+            if buckets.get(bucket).map_or(false, BTreeMap::is_empty) {
+                buckets.remove(bucket);
+            }
+            if buckets.is_empty() {
+                self.collections.remove(collection);
+            }
+        }
+    }
+}
+
+fn bucket_words(objects: &Bucket) -> BTreeSet<String> {
+    objects
+        .values()
+        .flat_map(|object| object.words.iter().cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(index: &mut FakeIndex, raw: &str) -> MockResponse {
+        let command = raw.split_whitespace().next().unwrap();
+        index.handle(command, raw).unwrap()
+    }
+
+    #[test]
+    fn should_parse_command_like_sonic() {
+        let cmd =
+            FakeCommand::parse("QUERY col buc \"say \\\"hi\\\"\" LIMIT(5) OFFSET(1)").unwrap();
+        assert_eq!(cmd.args, vec!["QUERY", "col", "buc"]);
+        assert_eq!(cmd.text.as_deref(), Some("say \"hi\""));
+        assert_eq!(cmd.meta("LIMIT", 10), Some(5));
+        assert_eq!(cmd.meta("OFFSET", 0), Some(1));
+
+        let cmd = FakeCommand::parse("LIST col buc LIMIT(2)").unwrap();
+        assert_eq!(cmd.args, vec!["LIST", "col", "buc"]);
+        assert_eq!(cmd.meta("LIMIT", 100), Some(2));
+
+        assert!(FakeCommand::parse("QUERY col buc \"unterminated").is_none());
+    }
+
+    #[test]
+    fn should_tokenize_text() {
+        assert_eq!(
+            tokenize("Sweet, Teriyaki-BEEF  skewers!"),
+            vec!["sweet", "teriyaki", "beef", "skewers"]
+        );
+    }
+
+    #[test]
+    fn should_find_recent_objects_first() {
+        let mut index = FakeIndex::default();
+        run(&mut index, "PUSH col buc obj:1 \"beef skewers\"");
+        run(&mut index, "PUSH col buc obj:2 \"beef teriyaki\"");
+
+        assert_eq!(
+            run(&mut index, "QUERY col buc \"beef\""),
+            MockResponse::event(["obj:2", "obj:1"])
+        );
+        assert_eq!(
+            run(&mut index, "QUERY col buc \"be sk\""),
+            MockResponse::event(["obj:1"])
+        );
+        assert_eq!(
+            run(&mut index, "QUERY col buc \"beef\" LIMIT(1) OFFSET(1)"),
+            MockResponse::event(["obj:1"])
+        );
+    }
+
+    #[test]
+    fn should_pop_and_flush_words() {
+        let mut index = FakeIndex::default();
+        run(&mut index, "PUSH col buc obj:1 \"beef skewers\"");
+        run(&mut index, "PUSH col buc obj:2 \"beef teriyaki\"");
+
+        assert_eq!(
+            run(&mut index, "POP col buc obj:1 \"beef pork\""),
+            MockResponse::Result(1)
+        );
+        assert_eq!(
+            run(&mut index, "COUNT col buc obj:1"),
+            MockResponse::Result(1)
+        );
+        assert_eq!(
+            run(&mut index, "FLUSHO col buc obj:2"),
+            MockResponse::Result(2)
+        );
+        assert_eq!(run(&mut index, "COUNT col buc"), MockResponse::Result(1));
+        assert_eq!(run(&mut index, "FLUSHC col"), MockResponse::Result(1));
+        assert_eq!(run(&mut index, "COUNT col"), MockResponse::Result(0));
+    }
+
+    #[test]
+    fn should_reject_invalid_command() {
+        let mut index = FakeIndex::default();
+        match run(&mut index, "PUSH col buc") {
+            MockResponse::Err(message) => assert!(message.starts_with("invalid_format")),
+            _ => unreachable!(),
+        }
+        assert!(index.handle("PING", "PING").is_none());
+    }
+}
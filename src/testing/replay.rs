@@ -0,0 +1,214 @@
+use super::{writeln_crlf, LocalListener};
+use crate::channels::redact;
+use crate::{Transcript, TranscriptLine};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader};
+use std::net::{Shutdown, SocketAddr, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long [`ReplayServer::assert_finished`] waits for the client.
+const FINISH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// In-process sonic server which replays the [`Transcript`] recorded by the
+/// [`TranscriptRecorder`](crate::TranscriptRecorder).
+///
+/// Every connection replays the next connection of the transcript: the server
+/// sends the recorded responses and checks that the client sends the same
+/// commands. The password of the `START` command isn't checked, because it's
+/// redacted in the transcript. On the first difference the server replies with
+/// `ERR replay_mismatch(...)` and closes the connection.
+///
+/// ```rust
+/// # use sonic_channel::*;
+/// # use sonic_channel::testing::*;
+/// # fn main() -> result::Result<()> {
+/// let transcript: Transcript = "\
+/// < CONNECTED <sonic-server v1.4.9>
+/// > START search ********
+/// < STARTED search protocol(1) buffer_size(20000)
+/// > QUERY search default \"Beef\"
+/// < PENDING a1b2c3
+/// < EVENT QUERY a1b2c3 recipe:1
+/// ".parse().unwrap();
+///
+/// let server = ReplayServer::start(&transcript).unwrap();
+/// let channel = SearchChannel::start(server.addr(), "SecretPassword")?;
+/// let objects = channel.query(QueryRequest::new(Dest::col("search"), "Beef"))?;
+/// assert_eq!(objects, vec!["recipe:1"]);
+///
+/// server.assert_finished();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ReplayServer {
+    listener: LocalListener,
+    state: Arc<Mutex<ReplayState>>,
+}
+
+#[derive(Debug, Default)]
+struct ReplayState {
+    /// Connections of the transcript which aren't started yet.
+    sessions: VecDeque<Vec<TranscriptLine>>,
+    /// Number of the lines of the started connections which aren't replayed yet.
+    remaining: usize,
+    mismatches: Vec<String>,
+    clients: Vec<TcpStream>,
+}
+
+impl ReplayServer {
+    /// Starts the server on an ephemeral port of the local interface.
+    pub fn start(transcript: &Transcript) -> io::Result<Self> {
+        let state = Arc::new(Mutex::new(ReplayState {
+            sessions: split_sessions(transcript),
+            ..Default::default()
+        }));
+
+        let client_state = state.clone();
+        let listener = LocalListener::start(move |stream| {
+            if let Err(err) = replay(stream, &client_state) {
+                log::debug!("[replay] client error: {}", err);
+            }
+        })?;
+
+        Ok(Self { listener, state })
+    }
+
+    /// Returns the address of the server.
+    pub fn addr(&self) -> SocketAddr {
+        self.listener.addr
+    }
+
+    /// Returns the differences between the commands sent by the clients and the
+    /// transcript.
+    pub fn mismatches(&self) -> Vec<String> {
+        self.lock().mismatches.clone()
+    }
+
+    /// Returns true if all lines of the transcript are replayed.
+    pub fn is_finished(&self) -> bool {
+        let state = self.lock();
+        state.sessions.is_empty() && state.remaining == 0
+    }
+
+    /// Panics if the client sent other commands than in the transcript, or if
+    /// the transcript isn't replayed to the end.
+    ///
+    /// The server may still be reading the last command, so it waits for the
+    /// client for a while.
+    pub fn assert_finished(&self) {
+        let started = Instant::now();
+        while !self.is_finished() && started.elapsed() < FINISH_TIMEOUT {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let state = self.lock();
+        assert!(
+            state.mismatches.is_empty(),
+            "the client doesn't follow the transcript: {:?}",
+            state.mismatches
+        );
+        assert!(
+            state.sessions.is_empty() && state.remaining == 0,
+            "the transcript isn't replayed: {} lines and {} connections are left",
+            state.remaining,
+            state.sessions.len()
+        );
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ReplayState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for ReplayServer {
+    fn drop(&mut self) {
+        for client in self.lock().clients.drain(..) {
+            let _ = client.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// Splits the transcript on the `CONNECTED` greetings of the server.
+fn split_sessions(transcript: &Transcript) -> VecDeque<Vec<TranscriptLine>> {
+    let mut sessions = VecDeque::<Vec<TranscriptLine>>::new();
+    for line in transcript.lines() {
+        let is_greeting =
+            matches!(line, TranscriptLine::Received(line) if line.starts_with("CONNECTED"));
+        match sessions.back_mut() {
+            Some(session) if !is_greeting => session.push(line.clone()),
+            _ => sessions.push_back(vec![line.clone()]),
+        }
+    }
+    sessions
+}
+
+fn replay(stream: TcpStream, state: &Mutex<ReplayState>) -> io::Result<()> {
+    let lock = || state.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let session = {
+        let mut state = lock();
+        state.clients.push(stream.try_clone()?);
+        match state.sessions.pop_front() {
+            Some(session) => {
+                state.remaining += session.len();
+                session
+            }
+            None => {
+                state.mismatches.push(String::from("unexpected connection"));
+                return stream.shutdown(Shutdown::Both);
+            }
+        }
+    };
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    for line in session {
+        match line {
+            TranscriptLine::Received(line) => {
+                // The line is counted before it's sent, so the client which
+                // got the last response sees the finished replay.
+                lock().remaining -= 1;
+                writeln_crlf(&mut writer, &line)?;
+            }
+            TranscriptLine::Sent(expected) => {
+                let actual = read_line(&mut reader)?;
+                if actual.as_deref().map(redact).as_deref() == Some(expected.as_str()) {
+                    lock().remaining -= 1;
+                    continue;
+                }
+
+                let mismatch = match actual {
+                    Some(actual) => format!("expected `{}`, got `{}`", expected, actual),
+                    None => format!("expected `{}`, the client closed connection", expected),
+                };
+                lock().mismatches.push(mismatch);
+                let _ = writeln_crlf(
+                    &mut writer,
+                    &format!("ERR replay_mismatch(expected {})", expected),
+                );
+                return writer.shutdown(Shutdown::Both);
+            }
+        }
+    }
+
+    if let Some(actual) = read_line(&mut reader)? {
+        lock()
+            .mismatches
+            .push(format!("unexpected `{}` after the transcript", actual));
+        let _ = writeln_crlf(&mut writer, "ERR replay_mismatch(end of transcript)");
+    }
+    writer.shutdown(Shutdown::Both)
+}
+
+/// Reads the line without the line ending, or `None` if the client closed the
+/// connection.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "async")]
+mod common;
+use common::*;
+
+const COLLECTION: &str = "Async";
+
+#[tokio::test]
+async fn should_push_and_find_object_with_async_channels() {
+    let bucket = "async_push_query";
+    let title = "Sweet Teriyaki Beef Skewers";
+
+    let dest = Dest::col_buc(COLLECTION, bucket);
+
+    let ingest_channel = AsyncIngestChannel::start(HOST, PASS)
+        .await
+        .expect("The Sonic server must be running");
+    ingest_channel
+        .push(PushRequest::new(dest.clone().obj("1"), title))
+        .await
+        .unwrap();
+
+    AsyncControlChannel::start(HOST, PASS)
+        .await
+        .unwrap()
+        .consolidate()
+        .await
+        .unwrap();
+
+    let search_channel = AsyncSearchChannel::start(HOST, PASS).await.unwrap();
+    match search_channel.query(QueryRequest::new(dest, title)).await {
+        Ok(object_ids) => assert_eq!(object_ids, vec![String::from("1")]),
+        Err(_) => unreachable!(),
+    }
+
+    flush_bucket(COLLECTION, bucket);
+}
+
+#[tokio::test]
+async fn should_share_async_channel_between_tasks() {
+    let bucket = "async_shared";
+
+    let dest = Dest::col_buc(COLLECTION, bucket);
+
+    let ingest_channel = ingest_start();
+    for (id, title) in [("1", "Beef Stew"), ("2", "Beef Skewers")] {
+        ingest_channel
+            .push(PushRequest::new(dest.clone().obj(id), title))
+            .unwrap();
+    }
+
+    consolidate();
+
+    let search_channel = std::sync::Arc::new(AsyncSearchChannel::start(HOST, PASS).await.unwrap());
+    let tasks = ["stew", "skewers"].map(|word| {
+        let channel = search_channel.clone();
+        let dest = dest.clone();
+        tokio::spawn(async move { channel.query(QueryRequest::new(dest, word)).await })
+    });
+
+    let mut results = Vec::new();
+    for task in tasks {
+        results.push(task.await.unwrap().unwrap());
+    }
+    assert_eq!(results, vec![vec!["1"], vec!["2"]]);
+
+    flush_bucket(COLLECTION, bucket);
+}
+
+#[tokio::test]
+async fn should_expose_server_info_of_async_channel() {
+    let channel = AsyncIngestChannel::start(HOST, PASS).await.unwrap();
+    let info = channel.server_info();
+    assert_eq!(info.mode, ChannelMode::Ingest);
+    assert_eq!(info.version, ingest_start().server_info().version);
+}
+
+#[tokio::test]
+async fn should_list_commands_of_async_channel() {
+    let channel = AsyncControlChannel::start(HOST, PASS).await.unwrap();
+    assert_eq!(
+        channel.commands().await.unwrap(),
+        control_start().commands().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn should_check_health_of_async_channel() {
+    let channel = AsyncSearchChannel::start(HOST, PASS).await.unwrap();
+    let health = channel.health_check().await.unwrap();
+    assert_eq!(health.mode, ChannelMode::Search);
+    assert_eq!(
+        health.max_buffer_size,
+        channel.server_info().max_buffer_size
+    );
+}
+
+/// Starts the server which replies to `QUERY` with the first term after the
+/// delay.
+fn start_slow_server(delay: std::time::Duration) -> std::net::SocketAddr {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        writer
+            .write_all(b"CONNECTED <sonic-server v1.4.9>\r\n")
+            .unwrap();
+        for (id, line) in BufReader::new(stream).lines().enumerate() {
+            let line = line.unwrap();
+            let reply = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["START", mode, ..] => format!("STARTED {} protocol(1) buffer_size(20000)", mode),
+                ["QUERY", _, _, terms, ..] => {
+                    std::thread::sleep(delay);
+                    format!(
+                        "PENDING {id}\r\nEVENT QUERY {id} {}",
+                        terms.trim_matches('"')
+                    )
+                }
+                _ => String::from("ERR unknown_command"),
+            };
+            writer
+                .write_all(format!("{}\r\n", reply).as_bytes())
+                .unwrap();
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn should_discard_reply_to_cancelled_command() {
+    let addr = start_slow_server(std::time::Duration::from_millis(200));
+    let channel = AsyncSearchChannel::start(addr, PASS).await.unwrap();
+
+    let cancelled = tokio::time::timeout(
+        std::time::Duration::from_millis(50),
+        channel.query(QueryRequest::new(Dest::col(COLLECTION), "first")),
+    )
+    .await;
+    assert!(cancelled.is_err());
+
+    let objects = channel
+        .query(QueryRequest::new(Dest::col(COLLECTION), "second"))
+        .await
+        .unwrap();
+    assert_eq!(objects, vec!["second"]);
+}
@@ -0,0 +1,115 @@
+#![cfg(all(feature = "cli", feature = "testing"))]
+
+use sonic_channel::testing::*;
+use std::process::{Command, Output};
+
+fn sonic_cli(fake: &FakeSonic, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sonic-cli"))
+        .env_remove("SONIC_URL")
+        .args(["--addr", &fake.addr().to_string(), "--password", "pass"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "sonic-cli failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn should_push_and_query_objects() {
+    let fake = FakeSonic::start().unwrap();
+
+    let output = sonic_cli(&fake, &["push", "recipes", "recipe:1", "Beef skewers"]);
+    assert_eq!(stdout(&output), "OK\n");
+
+    let output = sonic_cli(&fake, &["--format", "json", "query", "recipes", "beef"]);
+    assert_eq!(stdout(&output), "[\"recipe:1\"]\n");
+
+    let output = sonic_cli(&fake, &["count", "recipes", "--bucket", "default"]);
+    assert_eq!(stdout(&output), "count\n-----\n1\n");
+}
+
+#[test]
+fn should_report_server_error() {
+    let fake = FakeSonic::start().unwrap();
+    fake.server()
+        .respond("QUERY", MockResponse::Err(String::from("query_error(bad)")));
+
+    let output = sonic_cli(&fake, &["query", "recipes", "beef"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("error: "));
+}
+
+#[test]
+fn should_import_documents_from_file() {
+    let fake = FakeSonic::start().unwrap();
+    let path = std::env::temp_dir().join(format!("sonic-cli-{}.jsonl", std::process::id()));
+    std::fs::write(
+        &path,
+        "{\"id\": \"recipe:1\", \"title\": \"Beef skewers\"}\n\
+         {\"id\": \"recipe:2\", \"title\": \"Borscht\"}\n",
+    )
+    .unwrap();
+
+    let output = sonic_cli(
+        &fake,
+        &[
+            "--format",
+            "json",
+            "import",
+            path.to_str().unwrap(),
+            "--collection",
+            "recipes",
+            "--text-field",
+            "title",
+            "-j",
+            "2",
+        ],
+    );
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(report["imported"], 2);
+    assert_eq!(report["skipped"], 0);
+
+    let output = sonic_cli(&fake, &["--format", "json", "query", "recipes", "borscht"]);
+    assert_eq!(stdout(&output), "[\"recipe:2\"]\n");
+}
+
+#[test]
+fn should_require_format_of_json_file() {
+    let fake = FakeSonic::start().unwrap();
+    let path = std::env::temp_dir().join(format!("sonic-cli-{}.json", std::process::id()));
+    std::fs::write(
+        &path,
+        "{\"id\": \"recipe:1\", \"text\": \"Beef skewers\"}\n",
+    )
+    .unwrap();
+    let file = path.to_str().unwrap();
+
+    let output = sonic_cli(&fake, &["import", file, "--collection", "recipes"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--format"));
+
+    let output = sonic_cli(
+        &fake,
+        &[
+            "--format",
+            "json",
+            "import",
+            file,
+            "--format",
+            "jsonl",
+            "--collection",
+            "recipes",
+        ],
+    );
+    std::fs::remove_file(&path).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["imported"], 1);
+}
@@ -0,0 +1,42 @@
+mod common;
+use common::*;
+
+fn url(hosts: &str, mode: &str) -> String {
+    format!("sonic://:{}@{}/{}?connect_timeout=1s", PASS, hosts, mode)
+}
+
+#[test]
+fn should_start_channel_from_url() {
+    let search_channel: SearchChannel = ChannelBuilder::from_url(&url(HOST, "search"))
+        .unwrap()
+        .start()
+        .unwrap();
+
+    match search_channel.query(QueryRequest::new(Dest::col("Search"), "Beef")) {
+        Ok(_) => {}
+        Err(_) => unreachable!(),
+    }
+}
+
+#[test]
+fn should_connect_to_next_host_if_first_is_unavailable() {
+    // Nothing listens on the first port.
+    let hosts = format!("127.0.0.1:1,{}", HOST);
+    let res = ChannelBuilder::from_url(&url(&hosts, "ingest"))
+        .unwrap()
+        .start::<IngestChannel>();
+
+    assert!(res.is_ok());
+}
+
+#[test]
+fn should_fail_if_mode_does_not_match_channel() {
+    let res = ChannelBuilder::from_url(&url(HOST, "ingest"))
+        .unwrap()
+        .start::<SearchChannel>();
+
+    match res {
+        Err(result::Error::InvalidOptions(_)) => {}
+        _ => unreachable!(),
+    }
+}
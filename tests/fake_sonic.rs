@@ -0,0 +1,110 @@
+#![cfg(feature = "testing")]
+
+use sonic_channel::testing::*;
+use sonic_channel::*;
+
+const PASS: &str = "pass";
+
+fn start(fake: &FakeSonic) -> (IngestChannel, SearchChannel) {
+    (
+        IngestChannel::start(fake.addr(), PASS).unwrap(),
+        SearchChannel::start(fake.addr(), PASS).unwrap(),
+    )
+}
+
+#[test]
+fn should_find_pushed_objects() {
+    let fake = FakeSonic::start().unwrap();
+    let (ingest, search) = start(&fake);
+
+    let dest = Dest::col_buc("recipes", "user:1");
+    ingest
+        .push(PushRequest::new(
+            dest.clone().obj("recipe:1"),
+            "Beef skewers",
+        ))
+        .unwrap();
+    ingest
+        .push(PushRequest::new(
+            dest.clone().obj("recipe:2"),
+            "Teriyaki beef",
+        ))
+        .unwrap();
+
+    let objects = search
+        .query(QueryRequest::new(dest.clone(), "beef"))
+        .unwrap();
+    assert_eq!(objects, vec!["recipe:2", "recipe:1"]);
+
+    let objects = search
+        .query(QueryRequest::new(dest.clone(), "beef").limit(1).offset(1))
+        .unwrap();
+    assert_eq!(objects, vec!["recipe:1"]);
+
+    let other_bucket = Dest::col_buc("recipes", "user:2");
+    let objects = search
+        .query(QueryRequest::new(other_bucket, "beef"))
+        .unwrap();
+    assert!(objects.is_empty());
+}
+
+#[test]
+fn should_suggest_and_list_words() {
+    let fake = FakeSonic::start().unwrap();
+    let (ingest, search) = start(&fake);
+
+    let dest = Dest::col("recipes");
+    ingest
+        .push(PushRequest::new(
+            dest.clone().obj("recipe:1"),
+            "Beef, beer and beets",
+        ))
+        .unwrap();
+
+    let words = search
+        .suggest(SuggestRequest::new(dest.clone(), "bee"))
+        .unwrap();
+    assert_eq!(words, vec!["beef", "beer", "beets"]);
+
+    let words = search
+        .list(ListRequest::new(dest).limit(2).offset(1))
+        .unwrap();
+    assert_eq!(words, vec!["beef", "beer"]);
+}
+
+#[test]
+fn should_pop_count_and_flush() {
+    let fake = FakeSonic::start().unwrap();
+    let (ingest, search) = start(&fake);
+
+    let dest = Dest::col_buc("recipes", "user:1").obj("recipe:1");
+    ingest
+        .push(PushRequest::new(dest.clone(), "Sweet teriyaki beef"))
+        .unwrap();
+    assert_eq!(
+        ingest.pop(PopRequest::new(dest.clone(), "beef")).unwrap(),
+        1
+    );
+    assert_eq!(
+        ingest
+            .count(CountRequest::words("recipes", "user:1", "recipe:1"))
+            .unwrap(),
+        2
+    );
+
+    let objects = search
+        .query(QueryRequest::new(
+            Dest::col_buc("recipes", "user:1"),
+            "beef",
+        ))
+        .unwrap();
+    assert!(objects.is_empty());
+
+    assert_eq!(
+        ingest
+            .flush(FlushRequest::bucket("recipes", "user:1"))
+            .unwrap(),
+        1
+    );
+    assert_eq!(ingest.count(CountRequest::buckets("recipes")).unwrap(), 0);
+}
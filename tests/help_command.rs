@@ -0,0 +1,36 @@
+mod common;
+use common::*;
+
+#[test]
+fn should_list_manuals() {
+    let res = search_start().help(HelpRequest::Manuals).unwrap();
+    assert_eq!(res.manual, "manuals");
+    assert!(res.contains("commands"));
+}
+
+#[test]
+fn should_list_commands_of_each_mode() {
+    let commands = search_start().commands().unwrap();
+    assert!(commands.contains(&String::from("QUERY")));
+    assert!(!commands.contains(&String::from("PUSH")));
+
+    let commands = ingest_start().commands().unwrap();
+    assert!(commands.contains(&String::from("PUSH")));
+
+    let commands = control_start().commands().unwrap();
+    assert!(commands.contains(&String::from("TRIGGER")));
+
+    let channel = MultiplexedSearchChannel::start(HOST, PASS).unwrap();
+    assert_eq!(
+        channel.commands().unwrap(),
+        search_start().commands().unwrap()
+    );
+}
+
+#[test]
+fn should_fail_on_unknown_manual() {
+    match search_start().help(HelpRequest::Manual("unknown")) {
+        Err(result::Error::SonicServer(result::ServerError::NotFound)) => {}
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,164 @@
+#![cfg(all(feature = "import", feature = "testing"))]
+
+use sonic_channel::import::*;
+use sonic_channel::testing::*;
+use sonic_channel::*;
+use std::path::PathBuf;
+
+const RECIPES: &str = r#"{"id": "recipe:1", "title": "Beef skewers"}
+{"id": "recipe:2", "title": "Teriyaki beef"}
+{"id": "recipe:3", "title": ""}
+{"id": "recipe:4", "title": "Borscht"}
+{"id": "recipe:5", "title": "Beef stroganoff"}
+"#;
+
+fn importer(fake: &FakeSonic) -> Importer {
+    Importer::new(ChannelBuilder::new(fake.addr(), "pass")).mapping(
+        FieldMapping::default()
+            .collection(FieldSource::value("recipes"))
+            .text(["title"]),
+    )
+}
+
+fn checkpoint_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("sonic-{}-{}.checkpoint", name, std::process::id()))
+}
+
+fn query(fake: &FakeSonic, terms: &str) -> Vec<String> {
+    let search = SearchChannel::start(fake.addr(), "pass").unwrap();
+    let mut objects = search
+        .query(QueryRequest::new(Dest::col("recipes"), terms))
+        .unwrap();
+    objects.sort();
+    objects
+}
+
+#[test]
+fn should_import_jsonl_by_parallel_connections() {
+    let fake = FakeSonic::start().unwrap();
+
+    let report = importer(&fake)
+        .connections(3)
+        .import(RECIPES.as_bytes(), ImportFormat::Jsonl)
+        .unwrap();
+    assert_eq!((report.imported, report.skipped), (4, 1));
+    assert_eq!(report.resumed_from, 0);
+
+    assert_eq!(
+        query(&fake, "beef"),
+        vec!["recipe:1", "recipe:2", "recipe:5"]
+    );
+    let starts = fake.server().received_commands("START");
+    assert_eq!(starts.len(), 3 + 1);
+}
+
+#[test]
+fn should_import_csv_with_bucket_field() {
+    let fake = FakeSonic::start().unwrap();
+    let csv = "user,recipe,title,body\n\
+               user:1,recipe:1,Beef,\"Skewers, grilled\"\n\
+               user:2,recipe:2,Borscht,\n";
+
+    let report = Importer::new(ChannelBuilder::new(fake.addr(), "pass"))
+        .mapping(
+            FieldMapping::default()
+                .collection(FieldSource::value("recipes"))
+                .bucket(FieldSource::field("user"))
+                .object("recipe")
+                .text(["title", "body"]),
+        )
+        .import(csv.as_bytes(), ImportFormat::Csv)
+        .unwrap();
+    assert_eq!(report.imported, 2);
+
+    let pushes = fake.server().received_commands("PUSH");
+    assert!(pushes.contains(&String::from(
+        "PUSH recipes user:1 recipe:1 \"Beef Skewers, grilled\""
+    )));
+}
+
+#[test]
+fn should_split_large_texts() {
+    let fake = FakeSonic::start().unwrap();
+    fake.server().set_buffer_size(100);
+
+    let text = "beef ".repeat(100);
+    let input = format!("{{\"id\": \"recipe:1\", \"title\": \"{}\"}}\n", text);
+    importer(&fake)
+        .import(input.as_bytes(), ImportFormat::Jsonl)
+        .unwrap();
+
+    assert!(fake.server().received_commands("PUSH").len() > 1);
+}
+
+#[test]
+fn should_report_progress() {
+    let fake = FakeSonic::start().unwrap();
+    let progress = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let handler_progress = progress.clone();
+    importer(&fake)
+        .on_progress(move |p| {
+            handler_progress
+                .lock()
+                .unwrap()
+                .push(p.imported + p.skipped)
+        })
+        .import(RECIPES.as_bytes(), ImportFormat::Jsonl)
+        .unwrap();
+
+    assert_eq!(*progress.lock().unwrap(), vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn should_resume_failed_import_from_checkpoint() {
+    let fake = FakeSonic::start().unwrap();
+    let checkpoint = checkpoint_path("resume");
+    let _ = std::fs::remove_file(&checkpoint);
+
+    fake.server().respond("PUSH", MockResponse::Ok);
+    fake.server().respond("PUSH", MockResponse::Ok);
+    fake.server()
+        .respond("PUSH", MockResponse::Err(String::from("internal_error")));
+
+    let importer = importer(&fake).connections(1).checkpoint(&checkpoint);
+    let err = importer
+        .import(RECIPES.as_bytes(), ImportFormat::Jsonl)
+        .unwrap_err();
+    assert!(matches!(err.error, result::Error::SonicServer(_)));
+    assert_eq!(err.checkpoint.as_ref(), Some(&checkpoint));
+    // The third document has no text, so the fourth one failed.
+    assert_eq!(std::fs::read_to_string(&checkpoint).unwrap(), "3\n");
+
+    fake.server().clear_received();
+    let report = importer
+        .import(RECIPES.as_bytes(), ImportFormat::Jsonl)
+        .unwrap();
+    assert_eq!(report.resumed_from, 3);
+    assert_eq!(report.imported, 2);
+    assert_eq!(
+        fake.server().received_commands("PUSH"),
+        vec![
+            "PUSH recipes default recipe:4 \"Borscht\"",
+            "PUSH recipes default recipe:5 \"Beef stroganoff\"",
+        ]
+    );
+    assert!(!checkpoint.exists());
+}
+
+#[test]
+fn should_stop_on_invalid_document() {
+    let fake = FakeSonic::start().unwrap();
+    let input = "{\"id\": \"recipe:1\", \"title\": \"Beef\"}\n{\"title\": \"Borscht\"}\n";
+
+    let res = importer(&fake).import(input.as_bytes(), ImportFormat::Jsonl);
+    match res {
+        Err(ImportError {
+            error: result::Error::InvalidDocument(message),
+            checkpoint: None,
+        }) => {
+            assert_eq!(message, "line 2: missing `id` field")
+        }
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,15 @@
+#![cfg(feature = "control")]
+mod common;
+use common::*;
+
+#[test]
+fn should_get_server_stats() {
+    let channel = control_start();
+    match channel.info() {
+        Ok(stats) => {
+            assert!(stats.clients_connected >= 1);
+            assert!(stats.command_latency_best <= stats.command_latency_worst);
+        }
+        Err(_) => unreachable!(),
+    }
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "testing")]
+
+use sonic_channel::testing::*;
+use sonic_channel::*;
+
+#[test]
+fn should_query_scripted_objects() {
+    let server = MockSonicServer::start().unwrap();
+    server.respond("QUERY", MockResponse::event(["recipe:1", "recipe:2"]));
+
+    let channel = SearchChannel::start(server.addr(), "pass").unwrap();
+    let req = QueryRequest::new(Dest::col("search"), "Beef");
+    assert_eq!(channel.query(req).unwrap(), vec!["recipe:1", "recipe:2"]);
+
+    // The scripted response is used once.
+    let req = QueryRequest::new(Dest::col("search"), "Beef");
+    assert_eq!(channel.query(req).unwrap(), Vec::<String>::new());
+
+    assert_eq!(
+        server.received(),
+        vec![
+            "START search pass",
+            "QUERY search default \"Beef\"",
+            "QUERY search default \"Beef\"",
+        ]
+    );
+}
+
+#[test]
+fn should_record_ingest_commands() {
+    let server = MockSonicServer::start().unwrap();
+    server.respond("COUNT", MockResponse::Result(3));
+
+    let channel = IngestChannel::start(server.addr(), "pass").unwrap();
+    let dest = Dest::col_buc("search", "recipes").obj("recipe:1");
+    let pushed = channel.push(PushRequest::new(dest, "Beef").lang(Lang::Eng));
+    assert_eq!(pushed.unwrap(), 1);
+    assert_eq!(channel.count(CountRequest::buckets("search")).unwrap(), 3);
+
+    assert_eq!(
+        server.received_commands("push"),
+        vec!["PUSH search recipes recipe:1 \"Beef\" LANG(eng)"]
+    );
+}
+
+#[test]
+fn should_run_control_commands() {
+    let server = MockSonicServer::start().unwrap();
+
+    let channel = ControlChannel::start(server.addr(), "pass").unwrap();
+    channel.consolidate().unwrap();
+    assert_eq!(channel.info().unwrap().clients_connected, 1);
+    assert!(channel
+        .commands()
+        .unwrap()
+        .contains(&String::from("TRIGGER")));
+    assert_eq!(channel.server_info().max_buffer_size, MOCK_BUFFER_SIZE);
+}
+
+#[test]
+fn should_reply_with_scripted_error() {
+    let server = MockSonicServer::start().unwrap();
+    server.respond("QUERY", MockResponse::Err(String::from("query_error(bad)")));
+
+    let channel = SearchChannel::start(server.addr(), "pass").unwrap();
+    match channel.query(QueryRequest::new(Dest::col("search"), "Beef")) {
+        Err(result::Error::SonicServer(result::ServerError::QueryError(detail))) => {
+            assert_eq!(detail, "bad")
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn should_reject_wrong_password() {
+    let server = MockSonicServer::start().unwrap();
+    server.require_password("secret");
+
+    assert!(SearchChannel::start(server.addr(), "wrong").is_err());
+    assert!(SearchChannel::start(server.addr(), "secret").is_ok());
+}
+
+#[test]
+fn should_reconnect_after_disconnect() {
+    let server = MockSonicServer::start().unwrap();
+    let channel = SearchChannel::start(server.addr(), "pass").unwrap();
+    channel.ping().unwrap();
+
+    server.disconnect_clients();
+    channel.ping().unwrap();
+
+    assert_eq!(server.received_commands("START").len(), 2);
+}
+
+#[test]
+fn should_start_multiplexed_channel_with_builder() {
+    let server = MockSonicServer::start().unwrap();
+    server.respond("QUERY", MockResponse::event(["recipe:1"]));
+
+    let channel = ChannelBuilder::new(server.addr(), "pass")
+        .read_timeout(std::time::Duration::from_secs(1))
+        .start_multiplexed()
+        .unwrap();
+    let req = QueryRequest::new(Dest::col("search"), "Beef");
+    assert_eq!(channel.query(req).unwrap(), vec!["recipe:1"]);
+}
+
+#[test]
+fn should_stop_waiting_for_multiplexed_reply_after_read_timeout() {
+    let server = MockSonicServer::start().unwrap();
+    // The event never arrives.
+    server.respond(
+        "QUERY",
+        MockResponse::Lines(vec![String::from("PENDING a1")]),
+    );
+
+    let channel = ChannelBuilder::new(server.addr(), "pass")
+        .read_timeout(std::time::Duration::from_millis(100))
+        .start_multiplexed()
+        .unwrap();
+    let req = QueryRequest::new(Dest::col("search"), "Beef");
+    match channel.query(req) {
+        Err(result::Error::Timeout { .. }) => {}
+        _ => unreachable!(),
+    }
+
+    // The connection is still usable.
+    channel.ping().unwrap();
+}
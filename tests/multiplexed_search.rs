@@ -0,0 +1,50 @@
+mod common;
+use common::*;
+use std::sync::Arc;
+use std::thread;
+
+const COLLECTION: &str = "Search";
+
+#[test]
+fn should_run_concurrent_queries_over_one_connection() {
+    let bucket = "multiplexed_queries";
+
+    let dest = Dest::col_buc(COLLECTION, bucket);
+
+    let ingest_channel = ingest_start();
+    let titles = [
+        ("1", "Sweet Teriyaki Beef Skewers"),
+        ("2", "Slow Cooker Beef Stew I"),
+        ("3", "Christmas Prime Rib"),
+    ];
+    for (id, title) in titles {
+        ingest_channel
+            .push(PushRequest::new(dest.clone().obj(id), title))
+            .unwrap();
+    }
+
+    consolidate();
+
+    let search_channel = Arc::new(MultiplexedSearchChannel::start(HOST, PASS).unwrap());
+    let handles: Vec<_> = ["Teriyaki", "Stew", "Christmas"]
+        .into_iter()
+        .cycle()
+        .take(30)
+        .map(|word| {
+            let channel = search_channel.clone();
+            let dest = dest.clone();
+            thread::spawn(move || (word, channel.query(QueryRequest::new(dest, word)).unwrap()))
+        })
+        .collect();
+
+    for handle in handles {
+        match handle.join().unwrap() {
+            ("Teriyaki", object_ids) => assert_eq!(object_ids, vec!["1"]),
+            ("Stew", object_ids) => assert_eq!(object_ids, vec!["2"]),
+            ("Christmas", object_ids) => assert_eq!(object_ids, vec!["3"]),
+            _ => unreachable!(),
+        }
+    }
+
+    flush_bucket(COLLECTION, bucket);
+}
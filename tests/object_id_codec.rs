@@ -0,0 +1,74 @@
+mod common;
+use common::*;
+
+const COLLECTION: &str = "Search";
+
+fn start<C: SonicChannel<Channel = C>>() -> C {
+    ChannelBuilder::new(HOST, PASS)
+        .object_id_codec(UrlSafeCodec)
+        .start()
+        .expect("The Sonic server must be running")
+}
+
+#[test]
+fn should_return_original_object_ids() {
+    let bucket = "codec_composite_keys";
+    let key = "tenant/article 42";
+
+    let dest = Dest::col_buc(COLLECTION, bucket);
+
+    let ingest_channel: IngestChannel = start();
+    ingest_channel
+        .push(PushRequest::new(
+            dest.clone().obj(key),
+            "Sweet Teriyaki Beef Skewers",
+        ))
+        .unwrap();
+
+    consolidate();
+
+    let search_channel: SearchChannel = start();
+    match search_channel.query(QueryRequest::new(dest.clone(), "Beef")) {
+        Ok(object_ids) => assert_eq!(object_ids, vec![String::from(key)]),
+        Err(_) => unreachable!(),
+    }
+
+    match ingest_channel.flush(FlushRequest::from(dest.obj(key))) {
+        Ok(count) => assert!(count > 0),
+        Err(_) => unreachable!(),
+    }
+
+    flush_bucket(COLLECTION, bucket);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn should_split_push_by_encoded_object_id() {
+    use sonic_channel::testing::MockSonicServer;
+
+    let buffer_size = 120;
+    let server = MockSonicServer::start().unwrap();
+    server.set_buffer_size(buffer_size);
+
+    let ingest_channel: IngestChannel = ChannelBuilder::new(server.addr(), PASS)
+        .object_id_codec(UrlSafeCodec)
+        .start()
+        .unwrap();
+    let key = "tenant/article 42/section 7/paragraph 3";
+    ingest_channel
+        .push(
+            PushRequest::new(
+                Dest::col(COLLECTION).obj(key),
+                "Sweet Teriyaki Beef Skewers ".repeat(10),
+            )
+            .lang(Lang::Eng),
+        )
+        .unwrap();
+
+    let pushes = server.received_commands("PUSH");
+    assert!(pushes.len() > 1);
+    for push in pushes {
+        assert!(push.contains("tenant%2Farticle%2042"));
+        assert!(push.len() + "\r\n".len() <= buffer_size, "{}", push);
+    }
+}
@@ -0,0 +1,104 @@
+mod common;
+use common::*;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const COLLECTION: &str = "Search";
+
+#[test]
+fn should_reuse_returned_connection() {
+    let pool = Pool::<SearchChannel>::new(HOST, PASS, PoolConfig::default()).unwrap();
+    assert_eq!(pool.size(), 0);
+
+    for _ in 0..3 {
+        let channel = pool.get().unwrap();
+        channel
+            .query(QueryRequest::new(Dest::col(COLLECTION), "Beef"))
+            .unwrap();
+    }
+
+    assert_eq!(pool.size(), 1);
+    assert_eq!(pool.idle(), 1);
+}
+
+#[test]
+fn should_open_min_size_connections() {
+    let pool = Pool::<IngestChannel>::new(HOST, PASS, PoolConfig::default().min_size(2)).unwrap();
+    assert_eq!(pool.size(), 2);
+    assert_eq!(pool.idle(), 2);
+}
+
+#[test]
+fn should_fail_checkout_when_pool_is_exhausted() {
+    let config = PoolConfig::default()
+        .max_size(1)
+        .checkout_timeout(Some(Duration::from_millis(50)));
+    let pool = Pool::<SearchChannel>::new(HOST, PASS, config).unwrap();
+
+    let channel = pool.get().unwrap();
+    match pool.get() {
+        Err(result::Error::PoolTimeout) => {}
+        _ => unreachable!(),
+    }
+
+    drop(channel);
+    assert!(pool.get().is_ok());
+}
+
+#[test]
+fn should_close_discarded_connection() {
+    let pool = Pool::<SearchChannel>::new(HOST, PASS, PoolConfig::default()).unwrap();
+
+    pool.get().unwrap().discard();
+    assert_eq!(pool.size(), 0);
+}
+
+#[test]
+fn should_replace_discarded_connection_below_min_size() {
+    let pool = Pool::<SearchChannel>::new(HOST, PASS, PoolConfig::default().min_size(2)).unwrap();
+
+    pool.get().unwrap().discard();
+    assert_eq!(pool.size(), 1);
+
+    drop(pool.get().unwrap());
+    assert_eq!(pool.size(), 2);
+    assert_eq!(pool.idle(), 2);
+}
+
+#[test]
+fn should_share_pool_between_threads() {
+    let bucket = "pool_threads";
+
+    let dest = Dest::col_buc(COLLECTION, bucket);
+
+    ingest_start()
+        .push(PushRequest::new(
+            dest.clone().obj("1"),
+            "Sweet Teriyaki Beef Skewers",
+        ))
+        .unwrap();
+
+    consolidate();
+
+    let pool = Arc::new(
+        Pool::<SearchChannel>::new(HOST, PASS, PoolConfig::default().max_size(3)).unwrap(),
+    );
+    let handles: Vec<_> = (0..12)
+        .map(|_| {
+            let pool = pool.clone();
+            let dest = dest.clone();
+            thread::spawn(move || {
+                let channel = pool.get().unwrap();
+                channel.query(QueryRequest::new(dest, "Beef")).unwrap()
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), vec!["1"]);
+    }
+    assert!(pool.size() <= 3);
+
+    flush_bucket(COLLECTION, bucket);
+}
@@ -14,7 +14,7 @@ fn should_push_new_object_to_sonic() {
         dest.obj("1"),
         "Sweet Teriyaki Beef Skewers",
     )) {
-        Ok(()) => {}
+        Ok(1) => {}
         _ => unreachable!(),
     }
 
@@ -31,7 +31,7 @@ fn should_push_new_object_to_sonic_with_russian_locale() {
     match ingest_channel.push(
         PushRequest::new(dest.obj("1"), "Открытый пирог с орехами и сгущенкой").lang(Lang::Rus),
     ) {
-        Ok(()) => {}
+        Ok(1) => {}
         _ => unreachable!(),
     }
 
@@ -52,7 +52,52 @@ Skewers
 
     let ingest_channel = ingest_start();
     match ingest_channel.push(PushRequest::new(dest.obj("1"), multiline_text)) {
-        Ok(()) => {}
+        Ok(1) => {}
+        _ => unreachable!(),
+    }
+
+    flush_bucket(COLLECTION, bucket);
+}
+
+#[test]
+fn should_split_text_longer_than_buffer() {
+    let bucket = "push_long";
+    let dest = Dest::col_buc(COLLECTION, bucket);
+
+    let ingest_channel = ingest_start();
+    let max_buffer_size = ingest_channel.server_info().max_buffer_size;
+    let text = (0..max_buffer_size / 4)
+        .map(|i| format!("w{}", i))
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert!(text.len() > max_buffer_size);
+
+    match ingest_channel.push(PushRequest::new(dest.clone().obj("1"), &text)) {
+        Ok(chunks) => assert!(chunks > 1),
+        _ => unreachable!(),
+    }
+
+    match ingest_channel.pop(PopRequest::new(dest.obj("1"), &text)) {
+        Ok(count) => assert_eq!(count, max_buffer_size / 4),
+        _ => unreachable!(),
+    }
+
+    flush_bucket(COLLECTION, bucket);
+}
+
+#[test]
+fn should_reject_invalid_object_id() {
+    let bucket = "push_invalid_object";
+    let dest = Dest::col_buc(COLLECTION, bucket);
+
+    let ingest_channel = ingest_start();
+    match ingest_channel.push(PushRequest::new(dest.clone().obj("my obj"), "Beef")) {
+        Err(result::Error::InvalidIdentifier(_)) => {}
+        _ => unreachable!(),
+    }
+
+    match ingest_channel.push(PushRequest::new(dest.obj("1"), "Beef")) {
+        Ok(1) => {}
         _ => unreachable!(),
     }
 
@@ -57,6 +57,30 @@ None";
     flush_bucket(COLLECTION, bucket);
 }
 
+#[test]
+fn should_find_object_with_quoted_text() {
+    let bucket = "query_quoted";
+    let title = "The \"Sweet\" Teriyaki Beef C:\\";
+
+    let dest = Dest::col_buc(COLLECTION, bucket);
+
+    let ingest_channel = ingest_start();
+    match ingest_channel.push(PushRequest::new(dest.clone().obj("1"), title)) {
+        Ok(1) => {}
+        _ => unreachable!(),
+    }
+
+    consolidate();
+
+    let search_channel = search_start();
+    match search_channel.query(QueryRequest::new(dest, "\"Sweet\" Beef")) {
+        Ok(object_ids) => assert_eq!(object_ids, vec![String::from("1")]),
+        Err(_) => unreachable!(),
+    }
+
+    flush_bucket(COLLECTION, bucket);
+}
+
 #[test]
 fn should_find_many_objects() {
     let bucket = "query_many_objects";
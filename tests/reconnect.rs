@@ -0,0 +1,49 @@
+mod common;
+use common::*;
+
+const COLLECTION: &str = "Search";
+
+#[test]
+fn should_retry_idempotent_command_after_reconnect() {
+    let search_channel = search_start();
+    search_channel.quit().unwrap();
+
+    match search_channel.query(QueryRequest::new(Dest::col(COLLECTION), "Beef")) {
+        Ok(_) => {}
+        Err(_) => unreachable!(),
+    }
+}
+
+#[test]
+fn should_retry_not_idempotent_command_after_ended_connection() {
+    let bucket = "reconnect_push";
+
+    let dest = Dest::col_buc(COLLECTION, bucket);
+
+    let ingest_channel = ingest_start();
+    ingest_channel.quit().unwrap();
+
+    // The server ended the connection before the command, so it's safe to retry.
+    match ingest_channel.push(PushRequest::new(dest.obj("1"), "Beef")) {
+        Ok(1) => {}
+        _ => unreachable!(),
+    }
+
+    flush_bucket(COLLECTION, bucket);
+}
+
+#[test]
+fn should_not_reconnect_if_policy_is_disabled() {
+    let search_channel: SearchChannel = ChannelBuilder::new(HOST, PASS)
+        .reconnect(ReconnectPolicy::disabled())
+        .start()
+        .unwrap();
+    search_channel.quit().unwrap();
+
+    for _ in 0..2 {
+        match search_channel.query(QueryRequest::new(Dest::col(COLLECTION), "Beef")) {
+            Err(result::Error::WriteToStream { .. } | result::Error::ReadStream { .. }) => {}
+            _ => unreachable!(),
+        }
+    }
+}
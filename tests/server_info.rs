@@ -0,0 +1,37 @@
+mod common;
+use common::*;
+
+#[test]
+fn should_expose_server_info() {
+    let info = search_start().server_info();
+    assert_eq!(info.mode, ChannelMode::Search);
+    assert_eq!(info.protocol_version, 1);
+    assert!(info.max_buffer_size > 0);
+    assert!(info.banner.starts_with("<sonic-server"));
+    assert!(info.version.is_some());
+
+    assert_eq!(ingest_start().server_info().mode, ChannelMode::Ingest);
+    assert_eq!(control_start().server_info().mode, ChannelMode::Control);
+}
+
+#[test]
+fn should_expose_server_info_of_multiplexed_channel() {
+    let channel = MultiplexedSearchChannel::start(HOST, PASS).unwrap();
+    assert_eq!(channel.server_info(), search_start().server_info());
+}
+
+#[test]
+fn should_check_health_of_channels() {
+    let health = search_start().health_check().unwrap();
+    assert_eq!(health.mode, ChannelMode::Search);
+    assert!(health.max_buffer_size > 0);
+    assert_eq!(health.version, search_start().server_info().version);
+
+    assert_eq!(
+        control_start().health_check().unwrap().mode,
+        ChannelMode::Control
+    );
+
+    let channel = MultiplexedSearchChannel::start(HOST, PASS).unwrap();
+    assert_eq!(channel.health_check().unwrap().mode, ChannelMode::Search);
+}
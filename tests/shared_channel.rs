@@ -0,0 +1,29 @@
+mod common;
+use common::*;
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn should_share_channel_between_threads() {
+    let search_channel = Arc::new(search_start());
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let channel = search_channel.clone();
+            thread::spawn(move || {
+                (0..10)
+                    .map(|_| channel.query(QueryRequest::new(Dest::col("Search"), "Beef")))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        for res in handle.join().unwrap() {
+            match res {
+                Ok(_) => {}
+                Err(_) => unreachable!(),
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+mod common;
+use common::*;
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn should_fail_with_timeout_if_server_does_not_respond() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        // Accepts the connection, but never sends the `CONNECTED` banner.
+        let (_stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_millis(500));
+    });
+
+    let started_at = Instant::now();
+    let res = ChannelBuilder::new(addr, PASS)
+        .read_timeout(Duration::from_millis(100))
+        .start::<SearchChannel>();
+
+    match res {
+        Err(result::Error::Timeout { .. }) => {}
+        _ => unreachable!(),
+    }
+    assert!(started_at.elapsed() < Duration::from_millis(500));
+
+    server.join().unwrap();
+}
+
+#[test]
+fn should_run_commands_with_timeouts() {
+    let search_channel: SearchChannel = ChannelBuilder::new(HOST, PASS)
+        .connect_timeout(Duration::from_secs(1))
+        .read_timeout(Duration::from_secs(1))
+        .write_timeout(Duration::from_secs(1))
+        .start()
+        .unwrap();
+
+    match search_channel.query(QueryRequest::new(Dest::col("Search"), "Beef")) {
+        Ok(_) => {}
+        Err(_) => unreachable!(),
+    }
+}
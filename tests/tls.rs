@@ -0,0 +1,133 @@
+#![cfg(feature = "tls")]
+
+mod common;
+use common::*;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+const CA: &[u8] = include_bytes!("fixtures/ca.pem");
+const SERVER_CERT: &[u8] = include_bytes!("fixtures/server.pem");
+const SERVER_KEY: &[u8] = include_bytes!("fixtures/server.key");
+
+fn server_config() -> Arc<rustls::ServerConfig> {
+    let certs = CertificateDer::pem_slice_iter(SERVER_CERT)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let key = PrivateKeyDer::from_pem_slice(SERVER_KEY).unwrap();
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .unwrap();
+    Arc::new(config)
+}
+
+/// Accepts one TLS connection and answers every received line with the next
+/// scripted response. Returns the received lines.
+fn mock_tls_server(script: &'static [&'static str]) -> (SocketAddr, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let handle = thread::spawn(move || {
+        let (tcp, _) = listener.accept().unwrap();
+        let conn = rustls::ServerConnection::new(server_config()).unwrap();
+        let mut stream = BufReader::new(rustls::StreamOwned::new(conn, tcp));
+
+        let mut received = Vec::new();
+        let banner = "CONNECTED <sonic-server v1.4.0>\r\n";
+        if stream.get_mut().write_all(banner.as_bytes()).is_err() {
+            return received;
+        }
+
+        for response in script {
+            let mut line = String::new();
+            if matches!(stream.read_line(&mut line), Ok(0) | Err(_)) {
+                break;
+            }
+            received.push(line.trim_end().to_string());
+            let writer = stream.get_mut();
+            writer.write_all(response.as_bytes()).unwrap();
+            writer.flush().unwrap();
+        }
+
+        received
+    });
+
+    (addr, handle)
+}
+
+fn tls_config() -> TlsConfig {
+    TlsConfig::new().add_root_certificates_pem(CA).unwrap()
+}
+
+#[test]
+fn should_start_channel_over_tls() {
+    let (addr, server) = mock_tls_server(&[
+        "STARTED search protocol(1) buffer(20000)\r\n",
+        "PENDING Bt2m2gYa\r\nEVENT QUERY Bt2m2gYa user:1 user:2\r\n",
+        "ENDED quit\r\n",
+    ]);
+
+    let channel: SearchChannel = ChannelBuilder::new(addr, PASS)
+        .tls(tls_config().server_name("localhost"))
+        .start()
+        .unwrap();
+    let objects = channel
+        .query(QueryRequest::new(Dest::col("Search"), "Beef"))
+        .unwrap();
+    assert_eq!(objects, vec!["user:1", "user:2"]);
+    channel.quit().unwrap();
+
+    let received = server.join().unwrap();
+    assert_eq!(received[0], format!("START search {}", PASS));
+    assert_eq!(received[2], "QUIT");
+}
+
+#[test]
+fn should_use_host_of_address_as_server_name() {
+    let (addr, server) = mock_tls_server(&["STARTED search protocol(1) buffer(20000)\r\n"]);
+
+    let res = ChannelBuilder::new(format!("localhost:{}", addr.port()), PASS)
+        .tls(tls_config())
+        .start::<SearchChannel>();
+    assert!(res.is_ok());
+    drop(res);
+
+    server.join().unwrap();
+}
+
+#[test]
+fn should_reject_server_with_unknown_name() {
+    let (addr, server) = mock_tls_server(&[]);
+
+    let res = ChannelBuilder::new(addr, PASS)
+        .tls(tls_config().server_name("sonic.example.com"))
+        .start::<SearchChannel>();
+    match res {
+        Err(result::Error::Tls(_)) => {}
+        _ => unreachable!(),
+    }
+
+    server.join().unwrap();
+}
+
+#[test]
+fn should_reject_config_without_root_certificates() {
+    match TlsConfig::new().add_root_certificates_pem(b"") {
+        Err(result::Error::Tls(_)) => {}
+        _ => unreachable!(),
+    }
+
+    let res = ChannelBuilder::new("localhost:1491", PASS)
+        .tls(TlsConfig::new())
+        .start::<SearchChannel>();
+    match res {
+        Err(result::Error::Tls(_)) => {}
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "testing")]
+
+use sonic_channel::testing::*;
+use sonic_channel::*;
+use std::net::SocketAddr;
+
+fn search(addr: SocketAddr) -> Vec<String> {
+    let channel = SearchChannel::start(addr, "SecretPassword").unwrap();
+    channel
+        .query(QueryRequest::new(Dest::col("recipes"), "beef"))
+        .unwrap()
+}
+
+#[test]
+fn should_replay_recorded_session() {
+    let fake = FakeSonic::start().unwrap();
+    let ingest = IngestChannel::start(fake.addr(), "SecretPassword").unwrap();
+    ingest
+        .push(PushRequest::new(
+            Dest::col("recipes").obj("recipe:1"),
+            "Beef skewers",
+        ))
+        .unwrap();
+
+    let path = std::env::temp_dir().join(format!("sonic-{}.transcript", std::process::id()));
+    let recorder = TranscriptRecorder::create(&path).unwrap();
+    let channel: SearchChannel = ChannelBuilder::new(fake.addr(), "SecretPassword")
+        .record(recorder)
+        .start()
+        .unwrap();
+    let recorded = channel
+        .query(QueryRequest::new(Dest::col("recipes"), "beef"))
+        .unwrap();
+    drop(channel);
+
+    let transcript = Transcript::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        transcript.lines()[1],
+        TranscriptLine::Sent(format!("START search {}", REDACTED_PASSWORD))
+    );
+
+    let server = ReplayServer::start(&transcript).unwrap();
+    assert_eq!(search(server.addr()), recorded);
+    server.assert_finished();
+}
+
+#[test]
+fn should_report_different_commands() {
+    let transcript: Transcript = "\
+< CONNECTED <sonic-server v1.4.9>
+> START search ********
+< STARTED search protocol(1) buffer_size(20000)
+> QUERY recipes default \"pork\"
+< PENDING a
+< EVENT QUERY a recipe:2
+"
+    .parse()
+    .unwrap();
+
+    let server = ReplayServer::start(&transcript).unwrap();
+    let channel = ChannelBuilder::new(server.addr(), "SecretPassword")
+        .reconnect(ReconnectPolicy::disabled())
+        .start::<SearchChannel>()
+        .unwrap();
+    assert!(channel
+        .query(QueryRequest::new(Dest::col("recipes"), "beef"))
+        .is_err());
+
+    assert_eq!(
+        server.mismatches(),
+        vec!["expected `QUERY recipes default \"pork\"`, got `QUERY recipes default \"beef\"`"]
+    );
+    assert!(!server.is_finished());
+}
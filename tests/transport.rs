@@ -0,0 +1,16 @@
+mod common;
+use common::*;
+use std::net::TcpStream;
+
+#[test]
+fn should_start_channel_with_custom_connector() {
+    let search_channel: SearchChannel =
+        ChannelBuilder::with_connector(|| TcpStream::connect(HOST), PASS)
+            .start()
+            .unwrap();
+
+    match search_channel.query(QueryRequest::new(Dest::col("Search"), "Beef")) {
+        Ok(_) => {}
+        Err(_) => unreachable!(),
+    }
+}